@@ -16,9 +16,9 @@ impl fmt::Debug for BitPageWithPosition {
 pub struct BitPage;
 
 impl BitPage {
-    pub const MIN_VALUE: u64 = 0 as u64;
+    pub const MIN_VALUE: u64 = 0;
 
-    pub const MAX_VALUE: u64 = u64::max_value();
+    pub const MAX_VALUE: u64 = u64::MAX;
 
     pub const MAX_BITS: usize = 64;
 
@@ -56,6 +56,28 @@ impl BitPage {
         value.count_ones()
     }
 
+    /// number of active bits below bit_idx
+    #[inline]
+    pub fn rank(value: &u64, bit_idx: usize) -> u32 {
+        (value & zero_masks()[bit_idx]).count_ones()
+    }
+
+    /// bit_idx of the n-th (zero based) active bit
+    #[inline]
+    pub fn select(value: &u64, n: u32) -> Option<usize> {
+        if n >= value.count_ones() {
+            return None;
+        }
+
+        let mut value = *value;
+        for _ in 0..n {
+            // clear lowest active bit
+            value &= value - 1;
+        }
+
+        Some(value.trailing_zeros() as usize)
+    }
+
     #[inline]
     pub fn is_zeroes(value: &u64) -> bool {
         Self::MIN_VALUE.eq(value)
@@ -67,6 +89,21 @@ impl BitPage {
     }
 }
 
+/// splits a global bit index into its (page_idx, bit_idx) position
+#[inline]
+pub(crate) fn split_bit_index(index: u64) -> (usize, usize) {
+    (
+        (index / BitPage::MAX_BITS as u64) as usize,
+        (index % BitPage::MAX_BITS as u64) as usize,
+    )
+}
+
+/// joins a (page_idx, bit_idx) position back into a global bit index
+#[inline]
+pub(crate) fn join_bit_index(page_idx: usize, bit_idx: usize) -> u64 {
+    page_idx as u64 * BitPage::MAX_BITS as u64 + bit_idx as u64
+}
+
 fn masks_inner() -> [u64; BitPage::MAX_BITS] {
     let mut masks: [u64; BitPage::MAX_BITS] = [0; BitPage::MAX_BITS];
 
@@ -81,8 +118,8 @@ fn zero_masks_inner() -> [u64; BitPage::MAX_BITS] {
     let mut masks: [u64; BitPage::MAX_BITS] = [0; BitPage::MAX_BITS];
 
     let mut mask = 0;
-    for index in 0..BitPage::MAX_BITS {
-        masks[index] = mask;
+    for (index, value) in masks.iter_mut().enumerate() {
+        *value = mask;
 
         BitPage::set_bit(&mut mask, index);
     }
@@ -92,12 +129,12 @@ fn zero_masks_inner() -> [u64; BitPage::MAX_BITS] {
 
 #[inline]
 fn masks() -> &'static [u64; BitPage::MAX_BITS] {
-    &*MASKS
+    &MASKS
 }
 
 #[inline]
 pub fn zero_masks() -> &'static [u64; BitPage::MAX_BITS] {
-    &*ZERO_MASKS
+    &ZERO_MASKS
 }
 
 #[inline]
@@ -113,6 +150,21 @@ lazy_static! {
 #[cfg(test)]
 mod tests {
     use crate::bit_page::zero_masks_inner;
+    use crate::BitPage;
+
+    #[test]
+    fn test_rank_select() {
+        let value = 0b1011_0100u64 | (1 << 63);
+
+        assert_eq!(BitPage::rank(&value, 0), 0);
+        assert_eq!(BitPage::rank(&value, 5), 2);
+        assert_eq!(BitPage::rank(&value, 63), 4);
+
+        assert_eq!(BitPage::select(&value, 0), Some(2));
+        assert_eq!(BitPage::select(&value, 3), Some(7));
+        assert_eq!(BitPage::select(&value, 4), Some(63));
+        assert_eq!(BitPage::select(&value, 5), None);
+    }
 
     #[test]
     fn test_zero_masks() {
@@ -1,88 +1,100 @@
 // @author shailendra.sharma
-use std::ops::Range;
-use std::slice::Iter;
-
-use arrayvec::ArrayVec;
+use std::iter::FusedIterator;
 
 use crate::bit_page::BitPage;
 
-pub enum BitPageActiveBitsIterator {
-    AllZeroes,
-    AllOnes { range: Range<usize> },
-    Some { iter: Box<dyn Iterator<Item = usize>> },
+// set bits of a page are taken off the word itself... lowest one with trailing_zeros and x & (x - 1), highest one
+// with leading_zeros... so nothing is allocated or looked up per page
+#[derive(Copy, Clone, Debug)]
+pub struct BitPageActiveBitsIterator {
+    value: u64,
 }
 
-impl<'a> Iterator for BitPageActiveBitsIterator {
-    type Item = usize;
+impl BitPageActiveBitsIterator {
+    // writes the remaining bits (lowest first) as base + bit index until buf is full... returns how many were written
+    #[inline]
+    pub(crate) fn decode_into(&mut self, base: u64, buf: &mut [u64]) -> usize {
+        let mut len = 0;
+        for slot in buf.iter_mut() {
+            if self.value == 0 {
+                break;
+            }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        match self {
-            BitPageActiveBitsIterator::AllZeroes => None,
-            BitPageActiveBitsIterator::AllOnes { range } => range.next(),
-            BitPageActiveBitsIterator::Some { iter } => iter.next(),
+            *slot = base + self.value.trailing_zeros() as u64;
+            self.value &= self.value - 1;
+            len += 1;
         }
-    }
-}
-
-impl BitPage {
-    pub fn active_bits(value: u64) -> BitPageActiveBitsIterator {
-        match value {
-            BitPage::MIN_VALUE => BitPageActiveBitsIterator::AllZeroes,
-            BitPage::MAX_VALUE => BitPageActiveBitsIterator::AllOnes {
-                range: (0..BitPage::MAX_BITS),
-            },
-            _ => {
-                let mut byte_masks = Vec::<u8>::with_capacity(BitPage::NUM_BYTES);
-                for i in 0..BitPage::NUM_BYTES {
-                    let byte = (value >> (i * 8)) as u8;
-                    byte_masks.push(byte);
-                }
 
-                let iter = byte_masks
-                    .into_iter()
-                    .enumerate()
-                    .flat_map(|(byte_idx, byte_mask)| active_bits_iter(byte_mask).map(move |bit_idx| byte_idx * 8 + *bit_idx));
+        len
+    }
 
-                BitPageActiveBitsIterator::Some { iter: Box::new(iter) }
+    // same as decode_into... but highest bit first
+    #[inline]
+    pub(crate) fn decode_rev_into(&mut self, base: u64, buf: &mut [u64]) -> usize {
+        let mut len = 0;
+        for slot in buf.iter_mut() {
+            if self.value == 0 {
+                break;
             }
+
+            let bit_idx = BitPage::MAX_BITS as u64 - 1 - self.value.leading_zeros() as u64;
+            *slot = base + bit_idx;
+            self.value ^= 1 << bit_idx;
+            len += 1;
         }
+
+        len
     }
 }
 
-const ACTIVE_BITS_LEN: usize = u8::max_value() as usize + 1;
-type ActiveBitsType = ArrayVec<[Vec<usize>; ACTIVE_BITS_LEN]>;
+impl Iterator for BitPageActiveBitsIterator {
+    type Item = usize;
 
-lazy_static! {
-    static ref ACTIVE_BITS: ActiveBitsType = build_byte_to_active_bits();
-}
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.value == 0 {
+            return None;
+        }
 
-fn build_byte_to_active_bits() -> ActiveBitsType {
-    let mut array = ArrayVec::<[_; ACTIVE_BITS_LEN]>::new();
+        let bit_idx = self.value.trailing_zeros() as usize;
+        self.value &= self.value - 1;
 
-    for i in 0..ACTIVE_BITS_LEN {
-        array.push(build_active_bits(i as u8));
+        Some(bit_idx)
     }
 
-    array
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.value.count_ones() as usize;
+        (len, Some(len))
+    }
+
+    fn count(self) -> usize {
+        self.value.count_ones() as usize
+    }
 }
 
-fn build_active_bits(mut bit: u8) -> Vec<usize> {
-    let mut index = 0;
-    let mut bits = Vec::new();
-    while bit != 0 {
-        if bit & 1 == 1 {
-            bits.push(index);
+// active bits from the highest to the lowest
+impl DoubleEndedIterator for BitPageActiveBitsIterator {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.value == 0 {
+            return None;
         }
 
-        bit >>= 1;
-        index += 1;
-    }
+        let bit_idx = BitPage::MAX_BITS - 1 - self.value.leading_zeros() as usize;
+        self.value ^= 1 << bit_idx;
 
-    bits
+        Some(bit_idx)
+    }
 }
 
-fn active_bits_iter(byte: u8) -> Iter<'static, usize> {
-    ACTIVE_BITS[byte as usize].iter()
+impl ExactSizeIterator for BitPageActiveBitsIterator {}
+
+impl FusedIterator for BitPageActiveBitsIterator {}
+
+impl BitPage {
+    pub fn active_bits(value: u64) -> BitPageActiveBitsIterator {
+        BitPageActiveBitsIterator { value }
+    }
 }
 
 #[cfg(test)]
@@ -109,4 +121,48 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_active_bits_rev() {
+        for value in [0, 1 << 63, 0b1011_0000_0001, u64::MAX, 0x00F0_0000_8000_0001] {
+            let mut expected = BitPage::active_bits(value).collect_vec();
+            expected.reverse();
+
+            assert_eq!(BitPage::active_bits(value).rev().collect_vec(), expected);
+        }
+    }
+
+    #[test]
+    fn test_active_bits_decode() {
+        for value in [0, 1, 1 << 63, 0b1011_0000_0001, u64::MAX, 0x00F0_0000_8000_0001] {
+            let expected = (0..64).filter(|bit_idx| BitPage::is_bit_set(&value, *bit_idx)).collect_vec();
+            assert_eq!(BitPage::active_bits(value).collect_vec(), expected);
+            assert_eq!(BitPage::active_bits(value).len(), expected.len());
+
+            // both ends of the same word
+            let mut iter = BitPage::active_bits(value);
+            assert_eq!(iter.next(), expected.first().copied());
+            assert_eq!(iter.next_back(), if expected.len() > 1 { expected.last().copied() } else { None });
+
+            // a few bits at a time... as base + bit index
+            let mut iter = BitPage::active_bits(value);
+            let (mut buf, mut decoded) = ([0u64; 3], vec![]);
+            loop {
+                let len = iter.decode_into(640, &mut buf);
+                if len == 0 {
+                    break;
+                }
+                decoded.extend_from_slice(&buf[..len]);
+            }
+            assert_eq!(decoded, expected.iter().map(|bit_idx| 640 + *bit_idx as u64).collect_vec());
+
+            let mut iter = BitPage::active_bits(value);
+            let mut buf = [0u64; 64];
+            let len = iter.decode_rev_into(0, &mut buf);
+            assert_eq!(
+                buf[..len].to_vec(),
+                expected.iter().rev().map(|bit_idx| *bit_idx as u64).collect_vec()
+            );
+        }
+    }
 }
@@ -5,7 +5,7 @@ use crate::BitPage;
 
 // TODO: this is for backward compatibility of indices... as they gets changed... we can only encode u64 directly
 
-const MAX_VALUE: u64 = u64::max_value();
+const MAX_VALUE: u64 = u64::MAX;
 
 impl BitPage {
     pub fn encode<W>(value: u64, buf: &mut W)
@@ -26,6 +26,14 @@ impl BitPage {
         }
     }
 
+    /// bytes encode writes for the value
+    pub fn encoded_len(value: u64) -> usize {
+        match value {
+            0 | MAX_VALUE => 1,
+            _ => 9,
+        }
+    }
+
     pub fn decode<R>(buf: &mut R) -> anyhow::Result<u64>
     where
         R: Buf,
@@ -1,41 +1,66 @@
 // @author shailendra.sharma
 use std::fmt;
+use std::sync::Arc;
 
-use crate::bit_page::BitPageWithPosition;
-use crate::BitPage;
+use crate::bit_page::{join_bit_index, split_bit_index, BitPageWithPosition};
+use crate::bit_page_vec_containers::Container;
+use crate::bit_page_vec_iter::{swap_hole_pages, BitPageVecIter};
+use crate::bit_page_vec_resize::GrowFill;
+use crate::bit_page_vec_runs::BitRun;
+use crate::compaction_policy::DEFAULT_COMPACTION_POLICY;
+use crate::{BitPage, CompactionPolicy, Universe, UniversePolicy};
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum BitPageVecKind {
     AllZeroes,
     SparseWithZeroesHole,
     AllOnes,
     SparseWithOnesHole,
+    // sorted runs of active bits... holes are zeroes
+    Runs,
+    // per region containers of sorted offsets or bit pages... holes are zeroes
+    Containers,
 }
 
 #[derive(Clone)]
 pub struct BitPageVec {
     pub(crate) kind: BitPageVecKind,
     pub(crate) pages: Option<Vec<BitPageWithPosition>>,
+    pub(crate) runs: Option<Vec<BitRun>>,
+    pub(crate) containers: Option<Vec<Container>>,
     pub(crate) last_bit_index: (usize, usize),
+    // set_bit past last_bit_index grows the vector (instead of storing a bit outside of it)
+    pub(crate) auto_grow: bool,
+    // compaction of the vector when it is rebuilt by boolean ops or batches (default policy if none)
+    pub(crate) compaction_policy: Option<Arc<dyn CompactionPolicy>>,
+    // how binary operations combine this vector with one of a different universe
+    pub(crate) universe_policy: UniversePolicy,
 }
 
 impl BitPageVec {
-    pub fn new(kind: BitPageVecKind, pages: Option<Vec<BitPageWithPosition>>, last_bit_index: (usize, usize)) -> BitPageVec {
+    pub fn new<U: Into<Universe>>(kind: BitPageVecKind, pages: Option<Vec<BitPageWithPosition>>, universe: U) -> BitPageVec {
+        let last_bit_index = universe.into().last_bit_index();
+
         BitPageVec {
             kind,
             pages,
+            runs: None,
+            containers: None,
             last_bit_index,
+            auto_grow: false,
+            compaction_policy: None,
+            universe_policy: UniversePolicy::default(),
         }
     }
 
     #[inline]
-    pub fn all_zeros(last_bit_index: (usize, usize)) -> BitPageVec {
-        BitPageVec::new(BitPageVecKind::AllZeroes, None, last_bit_index)
+    pub fn all_zeros<U: Into<Universe>>(universe: U) -> BitPageVec {
+        BitPageVec::new(BitPageVecKind::AllZeroes, None, universe)
     }
 
     #[inline]
-    pub fn all_ones(last_bit_index: (usize, usize)) -> BitPageVec {
-        BitPageVec::new(BitPageVecKind::AllOnes, None, last_bit_index)
+    pub fn all_ones<U: Into<Universe>>(universe: U) -> BitPageVec {
+        BitPageVec::new(BitPageVecKind::AllOnes, None, universe)
     }
 
     pub fn kind(&self) -> &BitPageVecKind {
@@ -46,21 +71,84 @@ impl BitPageVec {
         self.last_bit_index
     }
 
+    pub fn universe(&self) -> Universe {
+        Universe::from(self.last_bit_index)
+    }
+
+    pub fn auto_grow(&self) -> bool {
+        self.auto_grow
+    }
+
+    pub fn set_auto_grow(&mut self, auto_grow: bool) {
+        self.auto_grow = auto_grow;
+    }
+
+    pub fn with_auto_grow(mut self, auto_grow: bool) -> BitPageVec {
+        self.auto_grow = auto_grow;
+        self
+    }
+
+    pub fn compaction_policy(&self) -> &dyn CompactionPolicy {
+        self.compaction_policy.as_deref().unwrap_or(&DEFAULT_COMPACTION_POLICY)
+    }
+
+    pub fn set_compaction_policy(&mut self, compaction_policy: Arc<dyn CompactionPolicy>) {
+        self.compaction_policy = Some(compaction_policy);
+    }
+
+    pub fn with_compaction_policy(mut self, compaction_policy: Arc<dyn CompactionPolicy>) -> BitPageVec {
+        self.compaction_policy = Some(compaction_policy);
+        self
+    }
+
+    pub fn universe_policy(&self) -> UniversePolicy {
+        self.universe_policy
+    }
+
+    pub fn set_universe_policy(&mut self, universe_policy: UniversePolicy) {
+        self.universe_policy = universe_policy;
+    }
+
+    pub fn with_universe_policy(mut self, universe_policy: UniversePolicy) -> BitPageVec {
+        self.universe_policy = universe_policy;
+        self
+    }
+
+    // replaces content of this vector... settings (like auto_grow) are kept
+    #[inline]
+    pub(crate) fn assign(&mut self, bit_page_vec: BitPageVec) {
+        let auto_grow = self.auto_grow;
+        let compaction_policy = self.compaction_policy.take();
+        let universe_policy = self.universe_policy;
+
+        *self = bit_page_vec;
+        self.auto_grow = auto_grow;
+        self.compaction_policy = compaction_policy;
+        self.universe_policy = universe_policy;
+    }
+
     #[inline]
     pub fn clear_bit(&mut self, page_idx: usize, bit_idx: usize) {
+        if (page_idx, bit_idx) >= self.last_bit_index {
+            // nothing is stored past the universe
+            return;
+        }
+
         match self.kind {
             BitPageVecKind::AllZeroes => {
                 // no-op
             }
+            BitPageVecKind::Runs => self.runs_clear_bit(join_bit_index(page_idx, bit_idx)),
+            BitPageVecKind::Containers => self.containers_clear_bit(page_idx, bit_idx),
             BitPageVecKind::AllOnes => {
                 let mut bit_page = BitPage::ones();
                 BitPage::clear_bit(&mut bit_page, bit_idx);
 
-                *self = BitPageVec::new(
+                self.assign(BitPageVec::new(
                     BitPageVecKind::SparseWithOnesHole,
                     Some(vec![BitPageWithPosition { page_idx, bit_page }]),
                     self.last_bit_index,
-                );
+                ));
             }
             BitPageVecKind::SparseWithZeroesHole => {
                 if let Some(ref mut pages) = self.pages {
@@ -77,7 +165,7 @@ impl BitPageVec {
 
                         // compact BitPageVec
                         if pages.is_empty() {
-                            *self = BitPageVec::all_zeros(self.last_bit_index);
+                            self.assign(BitPageVec::all_zeros(self.last_bit_index));
                         }
                     }
                 }
@@ -87,19 +175,9 @@ impl BitPageVec {
                     // do binary search for page_idx...
                     match pages.binary_search_by(|probe| probe.page_idx.cmp(&page_idx)) {
                         Ok(matching_index) => {
-                            // clear bit at the matching index
+                            // clear bit at the matching index... an all zeroes page stays stored as the hole is ones
                             let bit_page = &mut pages[matching_index].bit_page;
                             BitPage::clear_bit(bit_page, bit_idx);
-
-                            if BitPage::is_zeroes(bit_page) {
-                                // remove this bit page from matching index and compact page
-                                pages.remove(matching_index);
-                            }
-
-                            // compact BitPageVec
-                            if pages.is_empty() {
-                                *self = BitPageVec::all_zeros(self.last_bit_index);
-                            }
                         }
                         Err(insertion_index) => {
                             let mut bit_page = BitPage::ones();
@@ -115,20 +193,31 @@ impl BitPageVec {
 
     #[inline]
     pub fn set_bit(&mut self, page_idx: usize, bit_idx: usize) {
+        if (page_idx, bit_idx) >= self.last_bit_index {
+            if !self.auto_grow {
+                // bits past the universe are dropped at write time... so reads never disagree on them
+                return;
+            }
+
+            self.grow_to(split_bit_index(join_bit_index(page_idx, bit_idx) + 1), GrowFill::Zeroes);
+        }
+
         match self.kind {
             BitPageVecKind::AllZeroes => {
                 let mut bit_page = BitPage::zeroes();
                 BitPage::set_bit(&mut bit_page, bit_idx);
 
-                *self = BitPageVec::new(
+                self.assign(BitPageVec::new(
                     BitPageVecKind::SparseWithZeroesHole,
                     Some(vec![BitPageWithPosition { page_idx, bit_page }]),
                     self.last_bit_index,
-                );
+                ));
             }
             BitPageVecKind::AllOnes => {
                 // NO-OP
             }
+            BitPageVecKind::Runs => self.runs_set_bit(join_bit_index(page_idx, bit_idx)),
+            BitPageVecKind::Containers => self.containers_set_bit(page_idx, bit_idx),
             BitPageVecKind::SparseWithZeroesHole => {
                 if let Some(ref mut pages) = self.pages {
                     // do binary search for page_idx...
@@ -163,9 +252,15 @@ impl BitPageVec {
 
     #[inline]
     pub fn is_bit_set(&self, page_idx: usize, bit_idx: usize) -> bool {
+        if (page_idx, bit_idx) >= self.last_bit_index {
+            return false;
+        }
+
         match self.kind {
             BitPageVecKind::AllZeroes => false,
             BitPageVecKind::AllOnes => true,
+            BitPageVecKind::Runs => self.runs_is_bit_set(join_bit_index(page_idx, bit_idx)),
+            BitPageVecKind::Containers => self.containers_is_bit_set(page_idx, bit_idx),
             BitPageVecKind::SparseWithZeroesHole => {
                 if let Some(ref pages) = self.pages {
                     if let Ok(matching_index) = pages.binary_search_by(|probe| probe.page_idx.cmp(&page_idx)) {
@@ -188,8 +283,53 @@ impl BitPageVec {
         }
     }
 
+    // number of addressable bits... last_bit_index is exclusive
+    #[inline]
+    pub(crate) fn universe_len(&self) -> u64 {
+        self.universe().len()
+    }
+
+    // builds a vector from pages of a sparse vector whose missing pages have `hole` value...
+    // picks whichever of the zeroes hole and ones hole representation needs fewer pages
+    pub(crate) fn from_sparse_pages(hole: u64, pages: Vec<BitPageWithPosition>, last_bit_index: (usize, usize)) -> BitPageVec {
+        let universe = Universe::from(last_bit_index);
+        let swapped_len = universe
+            .pages()
+            .saturating_sub(pages.iter().filter(|page| page.bit_page == !hole).count());
+
+        let (hole, pages) = if swapped_len < pages.len() {
+            (!hole, swap_hole_pages(pages, universe, hole))
+        } else {
+            (hole, pages)
+        };
+
+        match (BitPage::is_zeroes(&hole), pages.is_empty()) {
+            (true, true) => BitPageVec::all_zeros(last_bit_index),
+            (true, false) => BitPageVec::new(BitPageVecKind::SparseWithZeroesHole, Some(pages), last_bit_index),
+            (false, true) => BitPageVec::all_ones(last_bit_index),
+            (false, false) => BitPageVec::new(BitPageVecKind::SparseWithOnesHole, Some(pages), last_bit_index),
+        }
+    }
+
+    // same as from_sparse_pages for zeroes hole pages... except a zeroes hole result is compacted further (into runs or containers)
+    pub(crate) fn from_active_pages(pages: Vec<BitPageWithPosition>, last_bit_index: (usize, usize)) -> BitPageVec {
+        match BitPageVec::from_sparse_pages(BitPage::zeroes(), pages, last_bit_index) {
+            BitPageVec {
+                kind: BitPageVecKind::SparseWithZeroesHole,
+                pages: Some(pages),
+                last_bit_index,
+                ..
+            } => BitPageVecIter::compact_sparse_with_zeroes_hole(pages, Universe::from(last_bit_index)),
+            bit_page_vec => bit_page_vec,
+        }
+    }
+
     pub fn size(&self) -> usize {
-        self.pages.as_ref().map_or_else(|| 0, |pages| pages.len())
+        match self.kind {
+            BitPageVecKind::Runs => self.runs.as_ref().map_or_else(|| 0, |runs| runs.len()),
+            BitPageVecKind::Containers => self.containers.as_ref().map_or_else(|| 0, |containers| containers.len()),
+            _ => self.pages.as_ref().map_or_else(|| 0, |pages| pages.len()),
+        }
     }
 
     pub(crate) fn count_ones(pages: Option<&Vec<BitPageWithPosition>>) -> u32 {
@@ -197,11 +337,11 @@ impl BitPageVec {
     }
 
     pub(crate) fn start_page(pages: Option<&Vec<BitPageWithPosition>>) -> Option<usize> {
-        pages.and_then(|pages| pages.get(0)).map(|page| page.page_idx)
+        pages.and_then(|pages| pages.first()).map(|page| page.page_idx)
     }
 
     pub(crate) fn end_page(pages: Option<&Vec<BitPageWithPosition>>) -> Option<usize> {
-        pages.and_then(|pages| pages.get(pages.len() - 1)).map(|page| page.page_idx)
+        pages.and_then(|pages| pages.last()).map(|page| page.page_idx)
     }
 }
 
@@ -228,6 +368,47 @@ impl fmt::Debug for BitPageVec {
                 BitPageVec::start_page(self.pages.as_ref()),
                 BitPageVec::end_page(self.pages.as_ref()),
             ),
+            BitPageVecKind::Runs => write!(
+                f,
+                "BitPageVec::Runs(len={}, last_bit_index={:?}, active_bits={}, start={:?}, end={:?}",
+                self.size(),
+                self.last_bit_index,
+                self.active_bits_count(),
+                self.runs.as_ref().and_then(|runs| runs.first()).map(|run| run.start()),
+                self.runs.as_ref().and_then(|runs| runs.last()).map(|run| run.end()),
+            ),
+            BitPageVecKind::Containers => write!(
+                f,
+                "BitPageVec::Containers(len={}, last_bit_index={:?}, active_bits={}, start={:?}, end={:?}",
+                self.size(),
+                self.last_bit_index,
+                self.active_bits_count(),
+                self.containers.as_ref().and_then(|containers| containers.first()),
+                self.containers.as_ref().and_then(|containers| containers.last()),
+            ),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::BitPageVec;
+
+    #[test]
+    fn test_clear_bit_empties_ones_hole_page() {
+        let mut bit_page_vec = BitPageVec::all_ones((3, 0));
+
+        // emptied page must not fall back to the ones hole
+        for bit_idx in 0..64 {
+            bit_page_vec.clear_bit(1, bit_idx);
+        }
+
+        assert!((0..64).all(|bit_idx| !bit_page_vec.is_bit_set(1, bit_idx)));
+        assert!(bit_page_vec.is_bit_set(0, 5));
+        assert!(bit_page_vec.is_bit_set(2, 5));
+
+        bit_page_vec.set_bit(1, 7);
+        assert!(bit_page_vec.is_bit_set(1, 7));
+        assert!(!bit_page_vec.is_bit_set(1, 8));
+    }
+}
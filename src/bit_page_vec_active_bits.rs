@@ -1,144 +1,70 @@
-use itertools::{EitherOrBoth, Itertools};
+use std::cmp::min;
 
 use crate::{BitPage, BitPageVec};
 // @author shailendra.sharma
-use crate::bit_page::{zero_masks, BitPageWithPosition};
+use crate::bit_page::split_bit_index;
 use crate::bit_page_vec::BitPageVecKind;
 
 impl BitPageVec {
     pub fn active_bits_count(&self) -> usize {
         match self.kind {
             BitPageVecKind::AllZeroes => 0,
-            // bit pages are zero based
-            BitPageVecKind::AllOnes => self.last_bit_index.0 * BitPage::MAX_BITS + (self.last_bit_index.1),
-            BitPageVecKind::SparseWithZeroesHole => {
-                // if log_enabled!(target: "bit_page_vec_log", Level::Debug) {
-                //     debug!(target: "bit_page_vec_log", "active_bits_count(kind=SparseWithZeroesHole) #pages={}", self.size());
-                // }
-
-                if let Some(ref pages) = self.pages {
-                    let last_page = self.last_bit_index.0;
-                    let last_bit = self.last_bit_index.1;
-                    pages
-                        .iter()
-                        .filter(move |value| value.page_idx <= last_page)
-                        .map(move |value| {
-                            if value.page_idx == last_page {
-                                let bit_page = value.bit_page & zero_masks()[last_bit];
-                                bit_page.count_ones() as usize
-                            } else {
-                                value.bit_page.count_ones() as usize
-                            }
-                        })
+            // universe is exclusive
+            BitPageVecKind::AllOnes => self.universe().len() as usize,
+            BitPageVecKind::Runs => {
+                let universe_len = self.universe_len();
+                self.runs.as_ref().map_or(0, |runs| {
+                    runs.iter()
+                        .map(|run| min(run.end(), universe_len).saturating_sub(run.start()) as usize)
                         .sum::<usize>()
-                } else {
-                    0
-                }
+                })
+            }
+            BitPageVecKind::Containers => {
+                let universe = self.universe();
+                self.containers.as_ref().map_or(0, |containers| {
+                    containers.iter().map(|container| container.count_within(universe)).sum::<usize>()
+                })
             }
-            BitPageVecKind::SparseWithOnesHole => {
+            BitPageVecKind::SparseWithZeroesHole | BitPageVecKind::SparseWithOnesHole => {
                 // if log_enabled!(target: "bit_page_vec_log", Level::Debug) {
-                //     debug!(target: "bit_page_vec_log", "active_bits_count(kind=SparseWithOnesHole) #pages={}", self.size());
+                //     debug!(target: "bit_page_vec_log", "active_bits_count(kind={:?}) #pages={}", self.kind, self.size());
                 // }
 
-                if let Some(ref pages) = self.pages {
-                    (0..self.last_bit_index.0)
-                        .merge_join_by(pages.iter(), |page_1_idx, BitPageWithPosition { page_idx: page_2_idx, .. }| {
-                            page_1_idx.cmp(page_2_idx)
-                        })
-                        .map(move |either| match either {
-                            EitherOrBoth::Both(_, BitPageWithPosition { bit_page, .. }) => bit_page.count_ones() as usize,
-                            EitherOrBoth::Left(_) => BitPage::MAX_BITS,
-                            EitherOrBoth::Right(BitPageWithPosition { .. }) => 0,
-                        })
-                        .sum::<usize>()
-                        + self.last_bit_index.1
-                } else {
-                    (0..self.last_bit_index.0).map(|_| BitPage::MAX_BITS).sum::<usize>() + self.last_bit_index.1
-                }
+                // pages are masked to the universe... so tail bits and pages past it are never counted
+                self.page_iter()
+                    .into_active_pages()
+                    .map(|(_, bit_page)| bit_page.count_ones() as usize)
+                    .sum::<usize>()
             }
         }
     }
 
-    pub fn active_bits(&self) -> BitPageVecActiveBitsIterator {
+    pub fn active_bits(&self) -> BitPageVecActiveBitsIterator<'_> {
         match self.kind {
             BitPageVecKind::AllZeroes => BitPageVecActiveBitsIterator::None,
-            BitPageVecKind::AllOnes => {
-                let iter = (0..self.last_bit_index.0)
-                    .flat_map(|page_idx| BitPage::active_bits(BitPage::ones()).map(move |bit_idx| (page_idx, bit_idx)))
-                    .chain(
-                        BitPage::active_bits(BitPage::ones())
-                            .filter(move |bit_idx| bit_idx.lt(&self.last_bit_index.1))
-                            .map(move |bit_idx| (self.last_bit_index.0, bit_idx)),
-                    );
-
-                BitPageVecActiveBitsIterator::Some { iter: Box::new(iter) }
-            }
-            BitPageVecKind::SparseWithZeroesHole => {
-                if let Some(ref pages) = self.pages {
-                    let last_page = self.last_bit_index.0;
-                    let last_bit = self.last_bit_index.1;
-                    let iter = pages.iter().filter(move |value| value.page_idx <= last_page).flat_map(
-                        move |BitPageWithPosition { page_idx, bit_page }| {
-                            BitPage::active_bits(*bit_page)
-                                .filter(move |bit_idx| page_idx.lt(&last_page) || bit_idx.lt(&last_bit))
-                                .map(move |bit_idx| (*page_idx, bit_idx))
-                        },
-                    );
+            BitPageVecKind::Runs => {
+                if let Some(ref runs) = self.runs {
+                    let universe_len = self.universe_len();
+                    let iter = runs
+                        .iter()
+                        .flat_map(move |run| run.start()..min(run.end(), universe_len))
+                        .map(split_bit_index);
 
                     BitPageVecActiveBitsIterator::Some { iter: Box::new(iter) }
                 } else {
                     BitPageVecActiveBitsIterator::None
                 }
             }
-            BitPageVecKind::SparseWithOnesHole => {
-                if let Some(ref pages) = self.pages {
-                    let iter = (0..=self.last_bit_index.0)
-                        .merge_join_by(pages.iter(), |page_1_idx, BitPageWithPosition { page_idx: page_2_idx, .. }| {
-                            page_1_idx.cmp(page_2_idx)
-                        })
-                        .flat_map(move |either| match either {
-                            EitherOrBoth::Both(_, BitPageWithPosition { page_idx, bit_page }) => {
-                                let iter: Box<dyn Iterator<Item = (usize, usize)>> =
-                                    Box::new(BitPage::active_bits(*bit_page).map(move |bit_idx| (*page_idx, bit_idx)));
-                                iter
-                            }
-                            EitherOrBoth::Left(page_idx) => {
-                                if page_idx.eq(&self.last_bit_index.0) {
-                                    let bit_page = BitPage::ones();
-                                    let iter: Box<dyn Iterator<Item = (usize, usize)>> = Box::new(
-                                        BitPage::active_bits(bit_page)
-                                            .filter(move |bit_idx| bit_idx.lt(&self.last_bit_index.1))
-                                            .map(move |bit_idx| (page_idx, bit_idx)),
-                                    );
-                                    iter
-                                } else {
-                                    let bit_page = BitPage::ones();
-                                    let iter: Box<dyn Iterator<Item = (usize, usize)>> =
-                                        Box::new(BitPage::active_bits(bit_page).map(move |bit_idx| (page_idx, bit_idx)));
-                                    iter
-                                }
-                            }
-                            EitherOrBoth::Right(BitPageWithPosition { page_idx, .. }) => {
-                                let bit_page = BitPage::zeroes();
-                                let iter: Box<dyn Iterator<Item = (usize, usize)>> =
-                                    Box::new(BitPage::active_bits(bit_page).map(move |bit_idx| (*page_idx, bit_idx)));
-                                iter
-                            }
-                        });
-
-                    BitPageVecActiveBitsIterator::Some { iter: Box::new(iter) }
-                } else {
-                    // duplicate of AllOnes case
-                    let iter = (0..self.last_bit_index.0)
-                        .flat_map(|page_idx| BitPage::active_bits(BitPage::ones()).map(move |bit_idx| (page_idx, bit_idx)))
-                        .chain(
-                            BitPage::active_bits(BitPage::ones())
-                                .filter(move |bit_idx| bit_idx.lt(&self.last_bit_index.1))
-                                .map(move |bit_idx| (self.last_bit_index.0, bit_idx)),
-                        );
+            BitPageVecKind::AllOnes
+            | BitPageVecKind::SparseWithZeroesHole
+            | BitPageVecKind::SparseWithOnesHole
+            | BitPageVecKind::Containers => {
+                let iter = self
+                    .page_iter()
+                    .into_active_pages()
+                    .flat_map(|(page_idx, bit_page)| BitPage::active_bits(bit_page).map(move |bit_idx| (page_idx, bit_idx)));
 
-                    BitPageVecActiveBitsIterator::Some { iter: Box::new(iter) }
-                }
+                BitPageVecActiveBitsIterator::Some { iter: Box::new(iter) }
             }
         }
     }
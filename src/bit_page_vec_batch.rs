@@ -0,0 +1,93 @@
+// @author shailendra.sharma
+use crate::bit_page::split_bit_index;
+use crate::bit_page_vec::BitPageVecKind;
+use crate::bit_page_vec_builder::BuilderPages;
+use crate::bit_page_vec_iter::BitPageVecIter;
+use crate::{BitPageVec, GrowFill};
+
+impl BitPageVec {
+    /// sets and then clears many ids in one merge pass... an id present in both gets cleared. ids needn't be sorted,
+    /// ids past the universe are dropped (unless auto grow is on)
+    pub fn apply_batch<S, C>(&mut self, sets: S, clears: C)
+    where
+        S: IntoIterator<Item = u64>,
+        C: IntoIterator<Item = u64>,
+    {
+        let (sets, clears) = (batch_pages(sets), batch_pages(clears));
+
+        if self.auto_grow && sets.end() > self.universe_len() {
+            self.grow_to(split_bit_index(sets.end()), GrowFill::Zeroes);
+        }
+
+        let universe = self.universe();
+        let sets = BitPageVecIter::from_pages(BitPageVecKind::SparseWithZeroesHole, sets.into_pages(), universe);
+        let clears = BitPageVecIter::from_pages(BitPageVecKind::SparseWithZeroesHole, clears.into_pages(), universe);
+
+        let result =
+            BitPageVecIter::and_not(BitPageVecIter::or(self.page_iter(), sets), clears).into_bit_page_vec_with(self.compaction_policy());
+
+        // compaction keeps the hole it was given... a batch may well have set (or cleared) every bit
+        let active_bits_count = result.active_bits_count() as u64;
+        if active_bits_count == 0 {
+            self.assign(BitPageVec::all_zeros(universe));
+        } else if active_bits_count == universe.len() {
+            self.assign(BitPageVec::all_ones(universe));
+        } else {
+            self.assign(result);
+        }
+    }
+}
+
+pub(crate) fn batch_pages<I: IntoIterator<Item = u64>>(ids: I) -> BuilderPages {
+    let mut pages = BuilderPages::default();
+    for index in ids {
+        pages.push(index);
+    }
+
+    pages
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::bit_page_vec::BitPageVecKind;
+    use crate::bit_page_vec_fixtures::vectors;
+    use crate::BitPageVec;
+
+    #[test]
+    fn test_apply_batch() {
+        let universe = (40, 9);
+        let vectors = vectors(universe);
+
+        // unsorted with duplicates... 3000 is past the universe and 650 is both set and cleared
+        let sets = [5u64, 3000, 650, 1, 1400, 651, 5, 2568, 720];
+        let clears = (0..2569u64).filter(|id| id % 11 == 0).chain([650, 101, 2400]).collect_vec();
+
+        for bit_page_vec in vectors.iter() {
+            let mut expected = bit_page_vec.clone();
+            sets.iter().filter(|id| **id < 2569).for_each(|id| expected.insert(*id));
+            clears.iter().for_each(|id| expected.remove(*id));
+
+            let mut batched = bit_page_vec.clone();
+            batched.apply_batch(sets.iter().copied(), clears.iter().copied());
+
+            assert_eq!(batched, expected, "{:?}", bit_page_vec);
+            assert!(!batched.contains(650));
+        }
+
+        // vectors move between kinds
+        let mut bit_page_vec = BitPageVec::all_zeros(universe);
+        bit_page_vec.apply_batch(0..2569, None);
+        assert!(matches!(bit_page_vec.kind(), BitPageVecKind::AllOnes));
+
+        bit_page_vec.apply_batch(None, 0..2569);
+        assert!(matches!(bit_page_vec.kind(), BitPageVecKind::AllZeroes));
+
+        // auto grow extends the universe to the largest set
+        let mut bit_page_vec = BitPageVec::all_zeros(universe).with_auto_grow(true);
+        bit_page_vec.apply_batch([3000, 10], [10]);
+        assert_eq!(bit_page_vec.universe().len(), 3001);
+        assert_eq!(bit_page_vec.iter().collect_vec(), vec![3000]);
+    }
+}
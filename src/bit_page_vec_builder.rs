@@ -0,0 +1,183 @@
+use std::cmp::max;
+
+use itertools::Itertools;
+
+// @author shailendra.sharma
+use crate::bit_page::{join_bit_index, split_bit_index, BitPageWithPosition};
+use crate::bit_page_vec::BitPageVecKind;
+use crate::bit_page_vec_iter::BitPageVecIter;
+use crate::{BitPage, BitPageVec, Universe};
+
+/// bulk builder of a BitPageVec... ascending ids (or words) are appended in amortized O(1), anything out of order
+/// falls back to sorting all pages on finish
+#[derive(Default)]
+pub struct BitPageVecBuilder {
+    pages: BuilderPages,
+    universe: Option<Universe>,
+}
+
+impl BitPageVecBuilder {
+    pub fn new() -> BitPageVecBuilder {
+        BitPageVecBuilder::default()
+    }
+
+    /// fixed universe of the result... by default it ends right after the largest id
+    pub fn with_universe<U: Into<Universe>>(mut self, universe: U) -> BitPageVecBuilder {
+        self.universe = Some(universe.into());
+        self
+    }
+
+    #[inline]
+    pub fn push(&mut self, index: u64) {
+        self.pages.push(index);
+    }
+
+    /// active bits of bit_page are added to page page_idx
+    #[inline]
+    pub fn push_word(&mut self, page_idx: usize, bit_page: u64) {
+        self.pages.push_word(page_idx, bit_page);
+    }
+
+    /// compacts the pages the same way as every other BitPageVec (e.g. runs, containers or ones hole)
+    pub fn finish(self) -> BitPageVec {
+        let universe = self.universe.unwrap_or_else(|| Universe::new(self.pages.end()));
+
+        // pages past the universe are dropped
+        BitPageVecIter::from_pages(BitPageVecKind::SparseWithZeroesHole, self.pages.into_pages(), universe).into_bit_page_vec()
+    }
+}
+
+impl Extend<u64> for BitPageVecBuilder {
+    fn extend<T: IntoIterator<Item = u64>>(&mut self, iter: T) {
+        for index in iter {
+            self.push(index);
+        }
+    }
+}
+
+// non-empty pages appended so far... sorted as long as every append was at or after the last page
+pub(crate) struct BuilderPages {
+    pages: Vec<BitPageWithPosition>,
+    sorted: bool,
+    // exclusive end of the largest active bit
+    end: u64,
+}
+
+impl Default for BuilderPages {
+    fn default() -> Self {
+        BuilderPages {
+            pages: Vec::new(),
+            sorted: true,
+            end: 0,
+        }
+    }
+}
+
+impl BuilderPages {
+    #[inline]
+    pub(crate) fn push(&mut self, index: u64) {
+        let (page_idx, bit_idx) = split_bit_index(index);
+
+        let mut bit_page = BitPage::zeroes();
+        BitPage::set_bit(&mut bit_page, bit_idx);
+
+        self.push_word(page_idx, bit_page);
+    }
+
+    pub(crate) fn push_word(&mut self, page_idx: usize, bit_page: u64) {
+        if BitPage::is_zeroes(&bit_page) {
+            return;
+        }
+
+        let highest_bit = BitPage::MAX_BITS - 1 - bit_page.leading_zeros() as usize;
+        self.end = max(self.end, join_bit_index(page_idx, highest_bit) + 1);
+
+        match self.pages.last_mut() {
+            Some(page) if page.page_idx == page_idx => page.bit_page |= bit_page,
+            Some(page) if page.page_idx > page_idx => {
+                // out of order... pages get sorted and merged on finish
+                self.sorted = false;
+                self.pages.push(BitPageWithPosition { page_idx, bit_page });
+            }
+            _ => self.pages.push(BitPageWithPosition { page_idx, bit_page }),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn end(&self) -> u64 {
+        self.end
+    }
+
+    pub(crate) fn into_pages(self) -> Vec<BitPageWithPosition> {
+        if self.sorted {
+            return self.pages;
+        }
+
+        let mut pages = self.pages;
+        pages.sort_by_key(|page| page.page_idx);
+
+        pages
+            .into_iter()
+            .coalesce(|page_one, page_two| {
+                if page_one.page_idx == page_two.page_idx {
+                    Ok(BitPageWithPosition {
+                        page_idx: page_one.page_idx,
+                        bit_page: page_one.bit_page | page_two.bit_page,
+                    })
+                } else {
+                    Err((page_one, page_two))
+                }
+            })
+            .collect_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::bit_page_vec::BitPageVecKind;
+    use crate::{BitPageVec, BitPageVecBuilder};
+
+    #[test]
+    fn test_builder() {
+        // sorted ids
+        let ids = (0..5_000u64).map(|id| id * 3 + id % 3).collect_vec();
+        let mut builder = BitPageVecBuilder::new();
+        builder.extend(ids.iter().copied());
+
+        let bit_page_vec = builder.finish();
+        assert_eq!(bit_page_vec.universe().len(), ids.last().unwrap() + 1);
+        assert_eq!(bit_page_vec.iter().collect_vec(), ids);
+
+        // unsorted ids and words... same as inserting them one by one
+        let mut builder = BitPageVecBuilder::new().with_universe((100, 0));
+        let mut expected = BitPageVec::all_zeros((100, 0));
+        for id in [700u64, 5, 6_000, 5, 64, 63, 7_000] {
+            builder.push(id);
+            expected.insert(id);
+        }
+
+        builder.push_word(2, 0b1010);
+        builder.push_word(1, 0b0110);
+        expected.set_bit(2, 1);
+        expected.set_bit(2, 3);
+        expected.set_bit(1, 1);
+        expected.set_bit(1, 2);
+
+        let bit_page_vec = builder.finish();
+        assert_eq!(bit_page_vec, expected);
+        // bits past the universe are dropped
+        assert!(!bit_page_vec.contains(7_000));
+
+        // dense words are compacted into a ones hole vector
+        let mut builder = BitPageVecBuilder::new();
+        for page_idx in 0..20_000 {
+            builder.push_word(page_idx, if page_idx % 3 == 0 { 0x5555_FFFF_FFFF_FFFF } else { u64::MAX });
+        }
+
+        let bit_page_vec = builder.finish();
+        assert!(matches!(bit_page_vec.kind(), BitPageVecKind::SparseWithOnesHole));
+        assert_eq!(bit_page_vec.active_bits_count(), 20_000 * 64 - 6_667 * 8);
+    }
+}
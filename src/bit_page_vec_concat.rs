@@ -0,0 +1,82 @@
+use itertools::Itertools;
+
+// @author shailendra.sharma
+use crate::bit_page_vec::BitPageVecKind;
+use crate::bit_page_vec_iter::{BitPageVecIter, PageItem};
+use crate::bit_page_vec_shift::shift_pages_up;
+use crate::{BitPageVec, Universe};
+
+impl BitPageVec {
+    /// stitches segments into one vector... ids of every segment are moved up by its offset
+    /// and the universe ends with the last segment. overlapping segments are or-ed
+    pub fn concat(segments: &[(u64, &BitPageVec)]) -> BitPageVec {
+        let universe = segments
+            .iter()
+            .map(|(offset, segment)| offset + segment.universe_len())
+            .max()
+            .unwrap_or(0);
+
+        let iter = segments
+            .iter()
+            .map(|(offset, segment)| shift_pages_up(segment.page_iter().into_active_pages(), *offset))
+            .kmerge_by(|(page_1_idx, _): &PageItem, (page_2_idx, _): &PageItem| page_1_idx < page_2_idx)
+            .coalesce(|(page_1_idx, page_one), (page_2_idx, page_two)| {
+                if page_1_idx == page_2_idx {
+                    Ok((page_1_idx, page_one | page_two))
+                } else {
+                    Err(((page_1_idx, page_one), (page_2_idx, page_two)))
+                }
+            });
+
+        // single pass over all the segments... compacted once at the end
+        BitPageVecIter::new(BitPageVecKind::SparseWithZeroesHole, Box::new(iter), Universe::new(universe)).into_bit_page_vec()
+    }
+
+    /// appends other with its ids moved up by offset... universe grows to cover it
+    pub fn append(&mut self, other: &BitPageVec, offset: u64) {
+        let bit_page_vec = BitPageVec::concat(&[(0, &*self), (offset, other)]);
+
+        self.assign(bit_page_vec);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::BitPageVec;
+
+    #[test]
+    fn test_concat() {
+        let mut ones_hole = BitPageVec::all_ones((10, 3));
+        ones_hole.remove(7);
+        ones_hole.clear_range(100..200);
+
+        let zeroes_hole = BitPageVec::from_ranges(vec![0..2, 60..70, 300..301], (5, 0));
+        let all_ones = BitPageVec::all_ones((0, 50));
+        let all_zeros = BitPageVec::all_zeros((3, 0));
+
+        let segments = [(0, &zeroes_hole), (320, &ones_hole), (1000, &all_zeros), (1192, &all_ones)];
+        let concat = BitPageVec::concat(&segments);
+
+        let expected = segments
+            .iter()
+            .flat_map(|(offset, segment)| segment.iter().map(move |id| id + offset))
+            .collect_vec();
+        assert_eq!(concat.universe().len(), 1242);
+        assert_eq!(concat.iter().collect_vec(), expected);
+
+        let mut appended = zeroes_hole.clone();
+        appended.append(&ones_hole, 320);
+        appended.append(&all_ones, 1192);
+        assert_eq!(appended, concat);
+
+        // overlapping segments are or-ed
+        let mut overlapping = zeroes_hole.clone();
+        overlapping.append(&zeroes_hole, 1);
+        assert_eq!(
+            overlapping.iter().collect_vec(),
+            vec![0, 1, 2, 60, 61, 62, 63, 64, 65, 66, 67, 68, 69, 70, 300, 301]
+        );
+    }
+}
@@ -0,0 +1,688 @@
+use std::cmp::min;
+use std::fmt;
+use std::mem::size_of;
+
+use itertools::{Either, EitherOrBoth, Itertools};
+
+// @author shailendra.sharma
+use crate::bit_page::{join_bit_index, split_bit_index, BitPageWithPosition};
+use crate::bit_page_vec::BitPageVecKind;
+use crate::bit_page_vec_iter::{BitPageVecIter, PageItem};
+use crate::{BitPage, BitPageVec, Universe, UniversePolicy};
+
+// number of pages in a container region... so that low offsets of a region fit in u16
+pub(crate) const CONTAINER_PAGES: usize = 1024;
+
+// array containers holding more offsets than this switch to bit pages
+pub(crate) const ARRAY_MAX_LEN: usize = 4096;
+
+// bytes needed per array offset and per stored bit page
+const ARRAY_OFFSET_BYTES: usize = 2;
+const PAGE_BYTES: usize = 16;
+
+/// roaring style container of one region of CONTAINER_PAGES pages... holes are zeroes
+#[derive(Clone)]
+pub enum Container {
+    // sorted low offsets of active bits within the region
+    Array {
+        region_idx: usize,
+        offsets: Vec<u16>,
+    },
+    // non-empty bit pages of the region (with absolute page_idx)
+    Pages {
+        region_idx: usize,
+        pages: Vec<BitPageWithPosition>,
+    },
+}
+
+impl Container {
+    #[inline]
+    pub fn region_idx(&self) -> usize {
+        match self {
+            Container::Array { region_idx, .. } | Container::Pages { region_idx, .. } => *region_idx,
+        }
+    }
+
+    pub fn count_ones(&self) -> usize {
+        match self {
+            Container::Array { offsets, .. } => offsets.len(),
+            Container::Pages { pages, .. } => pages.iter().map(|page| page.bit_page.count_ones() as usize).sum(),
+        }
+    }
+
+    // active bits of the container within universe... offsets are counted, not expanded into pages
+    pub(crate) fn count_within(&self, universe: Universe) -> usize {
+        match self {
+            Container::Array { region_idx, offsets } => {
+                let region_start = join_bit_index(region_idx * CONTAINER_PAGES, 0);
+                offsets.partition_point(|offset| region_start + (*offset as u64) < universe.len())
+            }
+            Container::Pages { pages, .. } => pages
+                .iter()
+                .take_while(|page| page.page_idx < universe.pages())
+                .map(|page| (page.bit_page & universe.page_mask(page.page_idx)).count_ones() as usize)
+                .sum(),
+        }
+    }
+
+    // intersection with a container of the same region... arrays are intersected over their offsets
+    fn and(&self, second: &Container) -> Container {
+        match (self, second) {
+            (
+                Container::Array { region_idx, offsets },
+                Container::Array {
+                    offsets: second_offsets, ..
+                },
+            ) => Container::Array {
+                region_idx: *region_idx,
+                offsets: and_offsets(offsets, second_offsets).collect_vec(),
+            },
+            (Container::Array { region_idx, offsets }, pages @ Container::Pages { .. })
+            | (pages @ Container::Pages { .. }, Container::Array { region_idx, offsets }) => Container::Array {
+                region_idx: *region_idx,
+                offsets: offsets
+                    .iter()
+                    .copied()
+                    .filter(|offset| pages.contains_offset(*offset))
+                    .collect_vec(),
+            },
+            (Container::Pages { region_idx, pages }, Container::Pages { pages: second_pages, .. }) => Container::Pages {
+                region_idx: *region_idx,
+                pages: and_pages(pages, second_pages).collect_vec(),
+            },
+        }
+    }
+
+    // same as and(second).count_ones()... nothing is collected
+    fn and_count(&self, second: &Container) -> usize {
+        match (self, second) {
+            (
+                Container::Array { offsets, .. },
+                Container::Array {
+                    offsets: second_offsets, ..
+                },
+            ) => and_offsets(offsets, second_offsets).count(),
+            (Container::Array { offsets, .. }, pages @ Container::Pages { .. })
+            | (pages @ Container::Pages { .. }, Container::Array { offsets, .. }) => {
+                offsets.iter().filter(|offset| pages.contains_offset(**offset)).count()
+            }
+            (Container::Pages { pages, .. }, Container::Pages { pages: second_pages, .. }) => {
+                and_pages(pages, second_pages).map(|page| page.bit_page.count_ones() as usize).sum()
+            }
+        }
+    }
+
+    #[inline]
+    fn contains_offset(&self, offset: u16) -> bool {
+        let offset = offset as usize;
+        self.is_bit_set(
+            self.region_idx() * CONTAINER_PAGES + offset / BitPage::MAX_BITS,
+            offset % BitPage::MAX_BITS,
+        )
+    }
+
+    // bytes of the offsets or pages held by the container
+    pub(crate) fn estimated_bytes(&self) -> usize {
+        match self {
+            Container::Array { offsets, .. } => offsets.len() * ARRAY_OFFSET_BYTES,
+            Container::Pages { pages, .. } => pages.len() * PAGE_BYTES,
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        match self {
+            Container::Array { offsets, .. } => offsets.is_empty(),
+            Container::Pages { pages, .. } => pages.is_empty(),
+        }
+    }
+
+    // pages of the container in page order
+    pub(crate) fn page_items(&self) -> impl Iterator<Item = PageItem> + '_ {
+        match self {
+            Container::Array { region_idx, offsets } => Either::Left(array_page_items(*region_idx, offsets.iter().copied())),
+            Container::Pages { pages, .. } => Either::Right(
+                pages
+                    .iter()
+                    .map(|BitPageWithPosition { page_idx, bit_page }| (*page_idx, *bit_page)),
+            ),
+        }
+    }
+
+    pub(crate) fn into_page_items(self) -> impl Iterator<Item = PageItem> {
+        match self {
+            Container::Array { region_idx, offsets } => Either::Left(array_page_items(region_idx, offsets.into_iter())),
+            Container::Pages { pages, .. } => Either::Right(
+                pages
+                    .into_iter()
+                    .map(|BitPageWithPosition { page_idx, bit_page }| (page_idx, bit_page)),
+            ),
+        }
+    }
+
+    // builds the cheaper container for non-empty pages of a single region
+    fn from_region_pages(region_idx: usize, pages: Vec<BitPageWithPosition>) -> Container {
+        let count = BitPageVec::count_ones(Some(&pages)) as usize;
+
+        if is_array(count, pages.len()) {
+            let offsets = pages
+                .iter()
+                .flat_map(|BitPageWithPosition { page_idx, bit_page }| {
+                    let page_offset = (page_idx - region_idx * CONTAINER_PAGES) * BitPage::MAX_BITS;
+                    BitPage::active_bits(*bit_page).map(move |bit_idx| (page_offset + bit_idx) as u16)
+                })
+                .collect_vec();
+
+            Container::Array { region_idx, offsets }
+        } else {
+            Container::Pages { region_idx, pages }
+        }
+    }
+
+    fn set_bit(&mut self, page_idx: usize, bit_idx: usize) {
+        match self {
+            Container::Array { region_idx, offsets } => {
+                let offset = region_offset(*region_idx, page_idx, bit_idx);
+                if let Err(insertion_index) = offsets.binary_search(&offset) {
+                    offsets.insert(insertion_index, offset);
+
+                    if offsets.len() > ARRAY_MAX_LEN {
+                        // dense enough... switch to bit pages
+                        let pages = array_page_items(*region_idx, offsets.iter().copied())
+                            .map(|(page_idx, bit_page)| BitPageWithPosition { page_idx, bit_page })
+                            .collect_vec();
+
+                        *self = Container::Pages {
+                            region_idx: *region_idx,
+                            pages,
+                        };
+                    }
+                }
+            }
+            Container::Pages { pages, .. } => match pages.binary_search_by(|probe| probe.page_idx.cmp(&page_idx)) {
+                Ok(matching_index) => BitPage::set_bit(&mut pages[matching_index].bit_page, bit_idx),
+                Err(insertion_index) => {
+                    let mut bit_page = BitPage::zeroes();
+                    BitPage::set_bit(&mut bit_page, bit_idx);
+
+                    pages.insert(insertion_index, BitPageWithPosition { page_idx, bit_page });
+                }
+            },
+        }
+    }
+
+    fn clear_bit(&mut self, page_idx: usize, bit_idx: usize) {
+        match self {
+            Container::Array { region_idx, offsets } => {
+                if let Ok(matching_index) = offsets.binary_search(&region_offset(*region_idx, page_idx, bit_idx)) {
+                    offsets.remove(matching_index);
+                }
+            }
+            Container::Pages { pages, .. } => {
+                if let Ok(matching_index) = pages.binary_search_by(|probe| probe.page_idx.cmp(&page_idx)) {
+                    let bit_page = &mut pages[matching_index].bit_page;
+                    BitPage::clear_bit(bit_page, bit_idx);
+
+                    if BitPage::is_zeroes(bit_page) {
+                        pages.remove(matching_index);
+                    }
+                }
+            }
+        }
+    }
+
+    fn is_bit_set(&self, page_idx: usize, bit_idx: usize) -> bool {
+        match self {
+            Container::Array { region_idx, offsets } => offsets.binary_search(&region_offset(*region_idx, page_idx, bit_idx)).is_ok(),
+            Container::Pages { pages, .. } => match pages.binary_search_by(|probe| probe.page_idx.cmp(&page_idx)) {
+                Ok(matching_index) => BitPage::is_bit_set(&pages[matching_index].bit_page, bit_idx),
+                Err(_) => false,
+            },
+        }
+    }
+
+    // number of active bits of the container below index
+    pub(crate) fn rank(&self, index: u64) -> u64 {
+        let (page_idx, bit_idx) = split_bit_index(index);
+
+        match self {
+            Container::Array { region_idx, offsets } => {
+                let region_start = join_bit_index(region_idx * CONTAINER_PAGES, 0);
+                let low = index.saturating_sub(region_start);
+
+                offsets.partition_point(|offset| (*offset as u64) < low) as u64
+            }
+            Container::Pages { pages, .. } => pages
+                .iter()
+                .take_while(|page| page.page_idx <= page_idx)
+                .map(|page| {
+                    if page.page_idx == page_idx {
+                        BitPage::rank(&page.bit_page, bit_idx) as u64
+                    } else {
+                        page.bit_page.count_ones() as u64
+                    }
+                })
+                .sum(),
+        }
+    }
+
+    // index of the n-th (zero based) active bit of the container
+    pub(crate) fn select(&self, n: u64) -> Option<u64> {
+        match self {
+            Container::Array { region_idx, offsets } => offsets
+                .get(n as usize)
+                .map(|offset| join_bit_index(region_idx * CONTAINER_PAGES, 0) + *offset as u64),
+            Container::Pages { pages, .. } => {
+                let mut before = 0;
+                for BitPageWithPosition { page_idx, bit_page } in pages {
+                    let count = bit_page.count_ones() as u64;
+                    if n < before + count {
+                        return BitPage::select(bit_page, (n - before) as u32).map(|bit_idx| join_bit_index(*page_idx, bit_idx));
+                    }
+
+                    before += count;
+                }
+
+                None
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Container {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Container::Array { region_idx, offsets } => write!(f, "Array(region={}, len={})", region_idx, offsets.len()),
+            Container::Pages { region_idx, pages } => write!(f, "Pages(region={}, len={})", region_idx, pages.len()),
+        }
+    }
+}
+
+impl BitPageVec {
+    pub(crate) fn from_containers<U: Into<Universe>>(containers: Vec<Container>, universe: U) -> BitPageVec {
+        let last_bit_index = universe.into().last_bit_index();
+
+        if containers.is_empty() {
+            BitPageVec::all_zeros(last_bit_index)
+        } else {
+            BitPageVec {
+                kind: BitPageVecKind::Containers,
+                pages: None,
+                runs: None,
+                containers: Some(containers),
+                last_bit_index,
+                auto_grow: false,
+                compaction_policy: None,
+                universe_policy: UniversePolicy::default(),
+            }
+        }
+    }
+
+    /// intersection of two containers vectors region by region (None for any other kinds)... array containers are
+    /// intersected over their sorted offsets instead of being streamed as pages
+    pub(crate) fn containers_and(&self, second: &BitPageVec) -> Option<BitPageVec> {
+        let (first_containers, second_containers) = match (self.kind, &self.containers, second.kind, &second.containers) {
+            (BitPageVecKind::Containers, Some(first), BitPageVecKind::Containers, Some(second)) => (first, second),
+            _ => return None,
+        };
+
+        // bits of either are within its own universe... so the intersection is the same for either universe policy
+        let universe = self.universe().combine(second.universe(), self.universe_policy());
+        let pages = region_pairs(first_containers, second_containers)
+            .flat_map(|(first, second)| first.and(second).into_page_items())
+            .map(|(page_idx, bit_page)| BitPageWithPosition { page_idx, bit_page })
+            .collect_vec();
+
+        Some(BitPageVecIter::compact_pages(
+            pages,
+            BitPage::zeroes(),
+            universe,
+            self.compaction_policy(),
+        ))
+    }
+
+    /// |self|, |second| (both within the universe they are combined into) and |self ∩ second| of two containers vectors
+    /// (None for any other kinds)... array containers are counted by their offsets
+    pub(crate) fn containers_counts(&self, second: &BitPageVec) -> Option<(usize, usize, usize)> {
+        let (first_containers, second_containers) = match (self.kind, &self.containers, second.kind, &second.containers) {
+            (BitPageVecKind::Containers, Some(first), BitPageVecKind::Containers, Some(second)) => (first, second),
+            _ => return None,
+        };
+
+        let universe = self.universe().combine(second.universe(), self.universe_policy());
+        let count = |containers: &[Container], own: Universe| {
+            let universe = min(own, universe);
+            containers.iter().map(|container| container.count_within(universe)).sum()
+        };
+        let and_count = region_pairs(first_containers, second_containers)
+            .map(|(first, second)| first.and_count(second))
+            .sum();
+
+        Some((count(first_containers, self.universe()), count(second_containers, second.universe()), and_count))
+    }
+
+    pub(crate) fn containers_set_bit(&mut self, page_idx: usize, bit_idx: usize) {
+        if let Some(ref mut containers) = self.containers {
+            let region_idx = page_idx / CONTAINER_PAGES;
+            match containers.binary_search_by(|probe| probe.region_idx().cmp(&region_idx)) {
+                Ok(matching_index) => containers[matching_index].set_bit(page_idx, bit_idx),
+                Err(insertion_index) => {
+                    let offsets = vec![region_offset(region_idx, page_idx, bit_idx)];
+                    containers.insert(insertion_index, Container::Array { region_idx, offsets });
+                }
+            }
+        }
+    }
+
+    pub(crate) fn containers_clear_bit(&mut self, page_idx: usize, bit_idx: usize) {
+        if let Some(ref mut containers) = self.containers {
+            let region_idx = page_idx / CONTAINER_PAGES;
+            if let Ok(matching_index) = containers.binary_search_by(|probe| probe.region_idx().cmp(&region_idx)) {
+                containers[matching_index].clear_bit(page_idx, bit_idx);
+
+                if containers[matching_index].is_empty() {
+                    containers.remove(matching_index);
+                }
+
+                if containers.is_empty() {
+                    self.assign(BitPageVec::all_zeros(self.last_bit_index));
+                }
+            }
+        }
+    }
+
+    pub(crate) fn containers_is_bit_set(&self, page_idx: usize, bit_idx: usize) -> bool {
+        if let Some(ref containers) = self.containers {
+            let region_idx = page_idx / CONTAINER_PAGES;
+            if let Ok(matching_index) = containers.binary_search_by(|probe| probe.region_idx().cmp(&region_idx)) {
+                return containers[matching_index].is_bit_set(page_idx, bit_idx);
+            }
+        }
+
+        false
+    }
+}
+
+// offsets are kept when they are cheaper than the pages of the region
+#[inline]
+fn is_array(count: usize, pages: usize) -> bool {
+    count <= ARRAY_MAX_LEN && count * ARRAY_OFFSET_BYTES < pages * PAGE_BYTES
+}
+
+#[inline]
+fn region_offset(region_idx: usize, page_idx: usize, bit_idx: usize) -> u16 {
+    ((page_idx - region_idx * CONTAINER_PAGES) * BitPage::MAX_BITS + bit_idx) as u16
+}
+
+// containers of the regions present in both (containers are sorted by region)
+fn region_pairs<'a>(first: &'a [Container], second: &'a [Container]) -> impl Iterator<Item = (&'a Container, &'a Container)> {
+    first
+        .iter()
+        .merge_join_by(second.iter(), |first, second| first.region_idx().cmp(&second.region_idx()))
+        .filter_map(|either| match either {
+            EitherOrBoth::Both(first, second) => Some((first, second)),
+            _ => None,
+        })
+}
+
+// offsets present in both (sorted) arrays
+fn and_offsets<'a>(first: &'a [u16], second: &'a [u16]) -> impl Iterator<Item = u16> + 'a {
+    first
+        .iter()
+        .merge_join_by(second.iter(), |first, second| first.cmp(second))
+        .filter_map(|either| match either {
+            EitherOrBoth::Both(offset, _) => Some(*offset),
+            _ => None,
+        })
+}
+
+// non-empty intersections of pages present in both
+fn and_pages<'a>(first: &'a [BitPageWithPosition], second: &'a [BitPageWithPosition]) -> impl Iterator<Item = BitPageWithPosition> + 'a {
+    first
+        .iter()
+        .merge_join_by(second.iter(), |first, second| first.page_idx.cmp(&second.page_idx))
+        .filter_map(|either| match either {
+            EitherOrBoth::Both(first, second) => Some(BitPageWithPosition {
+                page_idx: first.page_idx,
+                bit_page: first.bit_page & second.bit_page,
+            }),
+            _ => None,
+        })
+        .filter(|page| !BitPage::is_zeroes(&page.bit_page))
+}
+
+// pages of an array container... offsets are grouped lazily into pages
+fn array_page_items<I>(region_idx: usize, offsets: I) -> impl Iterator<Item = PageItem>
+where
+    I: Iterator<Item = u16>,
+{
+    let region_page = region_idx * CONTAINER_PAGES;
+
+    offsets
+        .map(move |offset| {
+            let offset = offset as usize;
+            let mut bit_page = BitPage::zeroes();
+            BitPage::set_bit(&mut bit_page, offset % BitPage::MAX_BITS);
+
+            (region_page + offset / BitPage::MAX_BITS, bit_page)
+        })
+        .coalesce(|(page_1_idx, page_one), (page_2_idx, page_two)| {
+            if page_1_idx == page_2_idx {
+                Ok((page_1_idx, page_one | page_two))
+            } else {
+                Err(((page_1_idx, page_one), (page_2_idx, page_two)))
+            }
+        })
+}
+
+// splits non-empty pages of a zeroes hole vector into per region containers
+pub(crate) fn pages_to_containers(pages: Vec<BitPageWithPosition>) -> Vec<Container> {
+    let mut containers = Vec::new();
+
+    let mut region_pages: Vec<BitPageWithPosition> = Vec::new();
+    for page in pages {
+        let region_idx = page.page_idx / CONTAINER_PAGES;
+        if let Some(first) = region_pages.first() {
+            if first.page_idx / CONTAINER_PAGES != region_idx {
+                let first_region_idx = first.page_idx / CONTAINER_PAGES;
+                containers.push(Container::from_region_pages(first_region_idx, std::mem::take(&mut region_pages)));
+            }
+        }
+
+        region_pages.push(page);
+    }
+
+    if let Some(first) = region_pages.first() {
+        let region_idx = first.page_idx / CONTAINER_PAGES;
+        containers.push(Container::from_region_pages(region_idx, region_pages));
+    }
+
+    containers
+}
+
+// estimated bytes of the containers pages_to_containers would build from non-empty pages... without building them
+pub(crate) fn containers_estimated_bytes(pages: &[BitPageWithPosition]) -> usize {
+    let mut bytes = 0;
+
+    let mut start = 0;
+    while start < pages.len() {
+        let region_idx = pages[start].page_idx / CONTAINER_PAGES;
+        let end = start + pages[start..].partition_point(|page| page.page_idx / CONTAINER_PAGES == region_idx);
+
+        let count = pages[start..end]
+            .iter()
+            .map(|page| page.bit_page.count_ones() as usize)
+            .sum::<usize>();
+        bytes += size_of::<Container>()
+            + if is_array(count, end - start) {
+                count * ARRAY_OFFSET_BYTES
+            } else {
+                (end - start) * PAGE_BYTES
+            };
+
+        start = end;
+    }
+
+    bytes
+}
+
+pub(crate) fn containers_to_pages(containers: Vec<Container>) -> Vec<BitPageWithPosition> {
+    containers
+        .into_iter()
+        .flat_map(Container::into_page_items)
+        .map(|(page_idx, bit_page)| BitPageWithPosition { page_idx, bit_page })
+        .collect_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use std::collections::BTreeSet;
+
+    use crate::bit_page_vec::BitPageVecKind;
+    use crate::bit_page_vec_containers::{pages_to_containers, Container, ARRAY_MAX_LEN, CONTAINER_PAGES};
+    use crate::bit_page_vec_iter::BitPageVecIter;
+    use crate::{BitPage, BitPageVec, CompactionPolicy, CompactionStats};
+
+    #[test]
+    fn test_sparse_pages_become_containers() {
+        let ids = (0..100_000u64).map(|id| id * 131).collect_vec();
+        let bit_page_vec = ids.iter().copied().collect::<BitPageVec>();
+
+        assert!(matches!(bit_page_vec.kind(), BitPageVecKind::Containers));
+        assert!(bit_page_vec
+            .containers
+            .as_ref()
+            .unwrap()
+            .iter()
+            .all(|container| matches!(container, Container::Array { .. })));
+
+        assert_eq!(bit_page_vec.active_bits_count(), ids.len());
+        assert_eq!(bit_page_vec.iter().collect_vec(), ids);
+        assert!(bit_page_vec.contains(131 * 500));
+        assert!(!bit_page_vec.contains(131 * 500 + 1));
+        assert_eq!(bit_page_vec.rank(131 * 500), 500);
+        assert_eq!(bit_page_vec.rank_index().select(500), Some(131 * 500));
+    }
+
+    #[test]
+    fn test_array_switches_to_pages() {
+        let mut bit_page_vec = (0..10u64).map(|id| id * 1000).collect::<BitPageVec>();
+        assert!(matches!(bit_page_vec.kind(), BitPageVecKind::Containers));
+
+        for id in 0..(ARRAY_MAX_LEN as u64 + 1) {
+            bit_page_vec.insert(id);
+        }
+
+        let containers = bit_page_vec.containers.as_ref().unwrap();
+        assert!(matches!(containers[0], Container::Pages { .. }));
+        assert_eq!(bit_page_vec.iter().take(3).collect_vec(), vec![0, 1, 2]);
+
+        for id in 0..(ARRAY_MAX_LEN as u64 + 1) {
+            bit_page_vec.remove(id);
+        }
+        for id in 0..10u64 {
+            bit_page_vec.remove(id * 1000);
+        }
+        assert!(matches!(bit_page_vec.kind(), BitPageVecKind::AllZeroes));
+    }
+
+    #[test]
+    fn test_mixed_containers_boolean_ops() {
+        let sparse = (0..1000u64).map(|id| id * 97).collect::<BitPageVec>();
+        let mut dense = BitPageVec::all_zeros((2000, 0));
+        dense.set_range(0..50_000);
+        dense.clear_range(20_000..20_100);
+
+        let and = BitPageVecIter::and(sparse.page_iter(), dense.page_iter()).into_bit_page_vec();
+        let expected = (0..1000u64)
+            .map(|id| id * 97)
+            .filter(|id| *id < 50_000 && !(20_000..20_100).contains(id))
+            .collect_vec();
+        assert_eq!(and.iter().collect_vec(), expected);
+
+        let or = BitPageVecIter::or(sparse.page_iter(), dense.page_iter()).into_bit_page_vec();
+        assert_eq!(or.active_bits_count(), 49_900 + (1000 - 516) + 1);
+    }
+
+    #[test]
+    fn test_containers_and_and_counts() {
+        let region_bits = (CONTAINER_PAGES * BitPage::MAX_BITS) as u64;
+
+        // region 0 is an array in both... region 1 is an array in one and pages in the other... region 2 is pages in both
+        let first_ids = (0..region_bits)
+            .filter(|id| id % 97 == 0)
+            .chain((region_bits..2 * region_bits).filter(|id| id % 89 == 0))
+            .chain((2 * region_bits..2 * region_bits + 20_000).filter(|id| id % 3 != 0))
+            .collect_vec();
+        let second_ids = (0..region_bits)
+            .filter(|id| id % 61 == 0)
+            .chain(region_bits..region_bits + 10_000)
+            .chain((2 * region_bits..2 * region_bits + 30_000).filter(|id| id % 2 == 0))
+            .chain(vec![5 * region_bits + 7])
+            .collect_vec();
+
+        let containers = |ids: &[u64], universe: u64| {
+            let pages = ids
+                .iter()
+                .copied()
+                .collect::<BitPageVec>()
+                .page_iter()
+                .into_bit_page_vec_with(&KeepPages);
+            BitPageVec::from_containers(pages_to_containers(pages.pages.unwrap()), universe)
+        };
+        let first = containers(&first_ids, 3 * region_bits);
+        let second = containers(&second_ids, 6 * region_bits);
+
+        let kinds = |bit_page_vec: &BitPageVec| {
+            bit_page_vec
+                .containers
+                .as_ref()
+                .unwrap()
+                .iter()
+                .map(|container| matches!(container, Container::Array { .. }))
+                .collect_vec()
+        };
+        assert_eq!(kinds(&first), vec![true, true, false]);
+        assert_eq!(kinds(&second), vec![true, false, false, true]);
+
+        // second is truncated to the universe of first
+        let first_set = first_ids.iter().copied().collect::<BTreeSet<_>>();
+        let second_set = second_ids
+            .iter()
+            .copied()
+            .filter(|id| *id < 3 * region_bits)
+            .collect::<BTreeSet<_>>();
+        let and_ids = first_set.intersection(&second_set).copied().collect_vec();
+
+        assert_eq!(first.active_bits_count(), first_set.len());
+        assert_eq!(second.active_bits_count(), second_ids.len());
+        assert_eq!(first.and_count(&second), and_ids.len());
+        assert_eq!(second.and_count(&first), and_ids.len());
+        assert_eq!(first.or_count(&second), first_set.union(&second_set).count());
+        assert_eq!(first.and_not_count(&second), first_set.difference(&second_set).count());
+        assert_eq!(second.and_not_count(&first), second_set.difference(&first_set).count());
+        assert_eq!(first.xor_count(&second), first_set.symmetric_difference(&second_set).count());
+
+        let mut and = first.clone();
+        and.and(&second);
+        assert_eq!(and.universe(), first.universe());
+        assert_eq!(and.iter().collect_vec(), and_ids);
+        assert_eq!(and, BitPageVecIter::and(first.page_iter(), second.page_iter()).into_bit_page_vec());
+
+        // nothing in common
+        let mut and = containers(&[1, 3, 5], region_bits);
+        and.and(&containers(&[2, 4, 6], region_bits));
+        assert!(matches!(and.kind(), BitPageVecKind::AllZeroes));
+    }
+
+    // keeps zeroes hole pages as they are
+    #[derive(Debug)]
+    struct KeepPages;
+
+    impl CompactionPolicy for KeepPages {
+        fn target(&self, _stats: &CompactionStats) -> BitPageVecKind {
+            BitPageVecKind::SparseWithZeroesHole
+        }
+    }
+}
@@ -0,0 +1,90 @@
+// @author shailendra.sharma
+use crate::bit_page_vec_iter::BitPageVecIter;
+use crate::BitPageVec;
+
+impl<'a> BitPageVecIter<'a> {
+    /// number of active bits in the stream... pages are counted as they are merged, nothing is collected
+    pub fn count_ones(self) -> usize {
+        self.into_active_pages()
+            .map(|(_, bit_page)| bit_page.count_ones() as usize)
+            .sum::<usize>()
+    }
+}
+
+impl BitPageVec {
+    /// |self ∩ second|
+    pub fn and_count(&self, second: &BitPageVec) -> usize {
+        if let Some((_, _, and_count)) = self.containers_counts(second) {
+            return and_count;
+        }
+
+        BitPageVecIter::and_with(self.page_iter(), second.page_iter(), self.universe_policy()).count_ones()
+    }
+
+    /// |self ∪ second|
+    pub fn or_count(&self, second: &BitPageVec) -> usize {
+        if let Some((first_count, second_count, and_count)) = self.containers_counts(second) {
+            return first_count + second_count - and_count;
+        }
+
+        BitPageVecIter::or_with(self.page_iter(), second.page_iter(), self.universe_policy()).count_ones()
+    }
+
+    /// |self \ second|
+    pub fn and_not_count(&self, second: &BitPageVec) -> usize {
+        if let Some((first_count, _, and_count)) = self.containers_counts(second) {
+            return first_count - and_count;
+        }
+
+        BitPageVecIter::and_not_with(self.page_iter(), second.page_iter(), self.universe_policy()).count_ones()
+    }
+
+    /// |self △ second|
+    pub fn xor_count(&self, second: &BitPageVec) -> usize {
+        if let Some((first_count, second_count, and_count)) = self.containers_counts(second) {
+            return first_count + second_count - 2 * and_count;
+        }
+
+        BitPageVecIter::xor_with(self.page_iter(), second.page_iter(), self.universe_policy()).count_ones()
+    }
+
+    /// |self ∩ second| / |self ∪ second|... two empty vectors are considered identical
+    pub fn jaccard(&self, second: &BitPageVec) -> f64 {
+        let or_count = self.or_count(second);
+        if or_count == 0 {
+            1.0
+        } else {
+            self.and_count(second) as f64 / or_count as f64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use crate::bit_page_vec_fixtures::vectors;
+
+    #[test]
+    fn test_counts() {
+        let vectors = vectors((50, 3));
+
+        for first in vectors.iter() {
+            for second in vectors.iter() {
+                let first_ids = first.iter().collect::<BTreeSet<_>>();
+                let second_ids = second.iter().collect::<BTreeSet<_>>();
+
+                let and_count = first_ids.intersection(&second_ids).count();
+                let or_count = first_ids.union(&second_ids).count();
+
+                assert_eq!(first.and_count(second), and_count, "{:?} & {:?}", first, second);
+                assert_eq!(first.or_count(second), or_count, "{:?} | {:?}", first, second);
+                assert_eq!(first.and_not_count(second), first_ids.difference(&second_ids).count());
+                assert_eq!(first.xor_count(second), first_ids.symmetric_difference(&second_ids).count());
+
+                let jaccard = if or_count == 0 { 1.0 } else { and_count as f64 / or_count as f64 };
+                assert!((first.jaccard(second) - jaccard).abs() < 1e-9);
+            }
+        }
+    }
+}
@@ -0,0 +1,1023 @@
+use std::cmp::{max, min, Ordering};
+use std::iter::Peekable;
+
+use itertools::EitherOrBoth;
+
+// @author shailendra.sharma
+use crate::bit_page::{join_bit_index, split_bit_index, BitPageWithPosition};
+use crate::bit_page_vec_containers::{Container, CONTAINER_PAGES};
+use crate::bit_page_vec_iter::{non_zero_page, or_merge_cross_types, xor_merge_cross_types, PageItem, PageIterator};
+use crate::bit_page_vec_range::range_mask;
+use crate::bit_page_vec_runs::BitRun;
+use crate::{BitPage, Universe};
+
+/// seekable stream of pages in ascending page order... the building block of joins
+pub trait PageCursor: Iterator<Item = PageItem> {
+    /// next page without consuming it
+    fn peek(&mut self) -> Option<PageItem>;
+
+    /// skips pages before page_idx... i.e. next page (if any) is at or after page_idx
+    fn advance_to(&mut self, page_idx: usize);
+}
+
+pub type PageCursorBox<'a> = Box<dyn PageCursor + 'a>;
+
+impl<C: PageCursor + ?Sized> PageCursor for Box<C> {
+    fn peek(&mut self) -> Option<PageItem> {
+        (**self).peek()
+    }
+
+    fn advance_to(&mut self, page_idx: usize) {
+        (**self).advance_to(page_idx)
+    }
+}
+
+// first position at or after from which is not before... probes 1, 2, 4... items ahead and then binary searches
+// the last window, so skipping k items costs O(log k)
+pub(crate) fn gallop<T, F>(items: &[T], from: usize, before: F) -> usize
+where
+    F: Fn(&T) -> bool,
+{
+    let (mut low, mut high, mut step) = (from, from, 1);
+    while high < items.len() && before(&items[high]) {
+        low = high + 1;
+        high = from + step;
+        step *= 2;
+    }
+
+    let high = min(high, items.len());
+    low + items[low..high].partition_point(before)
+}
+
+// plain page iterator... can only skip ahead one page at a time
+pub(crate) struct IterPageCursor<'a> {
+    iter: Peekable<PageIterator<'a>>,
+}
+
+impl<'a> IterPageCursor<'a> {
+    pub(crate) fn new(iter: PageIterator<'a>) -> IterPageCursor<'a> {
+        IterPageCursor { iter: iter.peekable() }
+    }
+}
+
+impl<'a> Iterator for IterPageCursor<'a> {
+    type Item = PageItem;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+}
+
+impl<'a> PageCursor for IterPageCursor<'a> {
+    fn peek(&mut self) -> Option<PageItem> {
+        self.iter.peek().copied()
+    }
+
+    fn advance_to(&mut self, page_idx: usize) {
+        while self.iter.next_if(|(idx, _)| *idx < page_idx).is_some() {}
+    }
+}
+
+// stored pages of a sparse vector... borrowed or owned
+pub(crate) struct SlicePageCursor<P> {
+    pages: P,
+    position: usize,
+}
+
+impl<P: AsRef<[BitPageWithPosition]>> SlicePageCursor<P> {
+    pub(crate) fn new(pages: P) -> SlicePageCursor<P> {
+        SlicePageCursor { pages, position: 0 }
+    }
+}
+
+impl<P: AsRef<[BitPageWithPosition]>> Iterator for SlicePageCursor<P> {
+    type Item = PageItem;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.peek()?;
+        self.position += 1;
+
+        Some(item)
+    }
+}
+
+impl<P: AsRef<[BitPageWithPosition]>> PageCursor for SlicePageCursor<P> {
+    #[inline]
+    fn peek(&mut self) -> Option<PageItem> {
+        self.pages
+            .as_ref()
+            .get(self.position)
+            .map(|BitPageWithPosition { page_idx, bit_page }| (*page_idx, *bit_page))
+    }
+
+    fn advance_to(&mut self, page_idx: usize) {
+        self.position = gallop(self.pages.as_ref(), self.position, |page| page.page_idx < page_idx);
+    }
+}
+
+// pages of the runs of a runs vector... runs sharing a page are merged into it
+pub(crate) struct RunsPageCursor<R> {
+    runs: R,
+    position: usize,
+    page_idx: usize,
+}
+
+impl<R: AsRef<[BitRun]>> RunsPageCursor<R> {
+    pub(crate) fn new(runs: R) -> RunsPageCursor<R> {
+        RunsPageCursor {
+            runs,
+            position: 0,
+            page_idx: 0,
+        }
+    }
+}
+
+impl<R: AsRef<[BitRun]>> Iterator for RunsPageCursor<R> {
+    type Item = PageItem;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.peek()?;
+        self.page_idx = item.0 + 1;
+
+        Some(item)
+    }
+}
+
+impl<R: AsRef<[BitRun]>> PageCursor for RunsPageCursor<R> {
+    fn peek(&mut self) -> Option<PageItem> {
+        loop {
+            // runs ending before the page are done
+            let runs = self.runs.as_ref();
+            let page_start = join_bit_index(self.page_idx, 0);
+            self.position = gallop(runs, self.position, |run| run.end() <= page_start);
+
+            let run = runs.get(self.position)?;
+            let page_idx = max(self.page_idx, split_bit_index(run.start()).0);
+
+            let bit_page = runs[self.position..]
+                .iter()
+                .take_while(|run| split_bit_index(run.start()).0 <= page_idx)
+                .filter(|run| !run.is_empty())
+                .fold(BitPage::zeroes(), |bit_page, run| {
+                    bit_page | range_mask(page_idx, split_bit_index(run.start()), split_bit_index(run.end() - 1))
+                });
+
+            if !BitPage::is_zeroes(&bit_page) {
+                return Some((page_idx, bit_page));
+            }
+
+            self.page_idx = page_idx + 1;
+        }
+    }
+
+    fn advance_to(&mut self, page_idx: usize) {
+        self.page_idx = max(self.page_idx, page_idx);
+    }
+}
+
+// pages of the containers of a containers vector... one container is decoded at a time
+pub(crate) struct ContainersPageCursor<C> {
+    containers: C,
+    position: usize,
+    pages: Vec<PageItem>,
+    page_position: usize,
+}
+
+impl<C: AsRef<[Container]>> ContainersPageCursor<C> {
+    pub(crate) fn new(containers: C) -> ContainersPageCursor<C> {
+        ContainersPageCursor {
+            containers,
+            position: 0,
+            pages: Vec::new(),
+            page_position: 0,
+        }
+    }
+
+    // decodes containers until there is a pending page... false if there are no more pages
+    fn load(&mut self) -> bool {
+        while self.page_position >= self.pages.len() {
+            match self.containers.as_ref().get(self.position) {
+                Some(container) => {
+                    self.pages.clear();
+                    self.pages.extend(container.page_items());
+                    self.page_position = 0;
+                    self.position += 1;
+                }
+                None => return false,
+            }
+        }
+
+        true
+    }
+}
+
+impl<C: AsRef<[Container]>> Iterator for ContainersPageCursor<C> {
+    type Item = PageItem;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.peek()?;
+        self.page_position += 1;
+
+        Some(item)
+    }
+}
+
+impl<C: AsRef<[Container]>> PageCursor for ContainersPageCursor<C> {
+    fn peek(&mut self) -> Option<PageItem> {
+        if self.load() {
+            Some(self.pages[self.page_position])
+        } else {
+            None
+        }
+    }
+
+    fn advance_to(&mut self, page_idx: usize) {
+        let pending = &self.pages[min(self.page_position, self.pages.len())..];
+        if !matches!(pending.last(), Some((idx, _)) if *idx >= page_idx) {
+            // decoded container is done... jump straight to the region of page_idx
+            let region_idx = page_idx / CONTAINER_PAGES;
+            self.position = gallop(self.containers.as_ref(), self.position, |container| {
+                container.region_idx() < region_idx
+            });
+            self.pages.clear();
+            self.page_position = 0;
+        }
+
+        while self.load() {
+            self.page_position += self.pages[self.page_position..].partition_point(|(idx, _)| *idx < page_idx);
+            if self.page_position < self.pages.len() {
+                break;
+            }
+        }
+    }
+}
+
+// input of a combinator... remembers the page it peeked, so peeking reaches the input only once per page and seeking
+// only when that page is before the target
+pub(crate) struct PeekedPageCursor<'a> {
+    cursor: AnyPageCursor<'a>,
+    state: Peeked,
+    // peeked page... valid in Peeked::Page state
+    page_idx: usize,
+    bit_page: u64,
+}
+
+// kept apart from the page, so pages are copied field by field instead of as a whole Option
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Peeked {
+    Nothing,
+    Page,
+    End,
+}
+
+impl<'a> PeekedPageCursor<'a> {
+    pub(crate) fn new(cursor: AnyPageCursor<'a>) -> PeekedPageCursor<'a> {
+        PeekedPageCursor {
+            cursor,
+            state: Peeked::Nothing,
+            page_idx: 0,
+            bit_page: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for PeekedPageCursor<'a> {
+    type Item = PageItem;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.peek();
+        if self.state == Peeked::Page {
+            self.state = Peeked::Nothing;
+        }
+
+        item
+    }
+}
+
+impl<'a> PageCursor for PeekedPageCursor<'a> {
+    #[inline]
+    fn peek(&mut self) -> Option<PageItem> {
+        if self.state == Peeked::Nothing {
+            match self.cursor.next() {
+                Some((page_idx, bit_page)) => {
+                    self.state = Peeked::Page;
+                    self.page_idx = page_idx;
+                    self.bit_page = bit_page;
+                }
+                None => self.state = Peeked::End,
+            }
+        }
+
+        match self.state {
+            Peeked::Page => Some((self.page_idx, self.bit_page)),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn advance_to(&mut self, page_idx: usize) {
+        match self.state {
+            Peeked::Page if self.page_idx >= page_idx => {}
+            Peeked::End => {}
+            _ => {
+                self.state = Peeked::Nothing;
+                self.cursor.advance_to(page_idx);
+            }
+        }
+    }
+}
+
+// intersection of two zeroes hole cursors... each side seeks to the other's page (leapfrog), so a small side
+// gallops over a large one instead of stepping through all its pages
+pub(crate) struct AndPageCursor<'a> {
+    first: PeekedPageCursor<'a>,
+    second: PeekedPageCursor<'a>,
+    // next result page... both sides are already past it
+    pending: Option<PageItem>,
+}
+
+impl<'a> AndPageCursor<'a> {
+    pub(crate) fn new(first: AnyPageCursor<'a>, second: AnyPageCursor<'a>) -> AndPageCursor<'a> {
+        AndPageCursor {
+            first: PeekedPageCursor::new(first),
+            second: PeekedPageCursor::new(second),
+            pending: None,
+        }
+    }
+}
+
+impl<'a> AndPageCursor<'a> {
+    // next result page straight from the inputs
+    #[inline]
+    fn and_next(&mut self) -> Option<PageItem> {
+        loop {
+            let (page_1_idx, page_one) = self.first.peek()?;
+            self.second.advance_to(page_1_idx);
+
+            let (page_2_idx, page_two) = self.second.peek()?;
+            if page_1_idx < page_2_idx {
+                self.first.advance_to(page_2_idx);
+                continue;
+            }
+
+            self.first.next();
+            self.second.next();
+
+            let bit_page = page_one & page_two;
+            if !BitPage::is_zeroes(&bit_page) {
+                return Some((page_1_idx, bit_page));
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for AndPageCursor<'a> {
+    type Item = PageItem;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.pending.take() {
+            Some(item) => Some(item),
+            None => self.and_next(),
+        }
+    }
+}
+
+impl<'a> PageCursor for AndPageCursor<'a> {
+    fn peek(&mut self) -> Option<PageItem> {
+        if self.pending.is_none() {
+            self.pending = self.and_next();
+        }
+
+        self.pending
+    }
+
+    fn advance_to(&mut self, page_idx: usize) {
+        if matches!(self.pending, Some((idx, _)) if idx >= page_idx) {
+            return;
+        }
+
+        self.pending = None;
+        self.first.advance_to(page_idx);
+        self.second.advance_to(page_idx);
+    }
+}
+
+// intersection of a zeroes hole cursor with a ones hole cursor... result pages are a subset of the zeroes hole
+// pages, so the ones hole side is only ever seeked to them
+pub(crate) struct AndOnesHolePageCursor<'a> {
+    zeroes: PeekedPageCursor<'a>,
+    ones: PeekedPageCursor<'a>,
+    // next result page... zeroes side is already past it
+    pending: Option<PageItem>,
+}
+
+impl<'a> AndOnesHolePageCursor<'a> {
+    pub(crate) fn new(zeroes: AnyPageCursor<'a>, ones: AnyPageCursor<'a>) -> AndOnesHolePageCursor<'a> {
+        AndOnesHolePageCursor {
+            zeroes: PeekedPageCursor::new(zeroes),
+            ones: PeekedPageCursor::new(ones),
+            pending: None,
+        }
+    }
+}
+
+impl<'a> AndOnesHolePageCursor<'a> {
+    // next result page straight from the inputs
+    #[inline]
+    fn and_next(&mut self) -> Option<PageItem> {
+        loop {
+            let (page_idx, mut bit_page) = self.zeroes.next()?;
+            self.ones.advance_to(page_idx);
+
+            match self.ones.peek() {
+                Some((ones_page_idx, ones_page)) if ones_page_idx == page_idx => bit_page &= ones_page,
+                _ => {
+                    // some & 1
+                }
+            }
+
+            if !BitPage::is_zeroes(&bit_page) {
+                return Some((page_idx, bit_page));
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for AndOnesHolePageCursor<'a> {
+    type Item = PageItem;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.pending.take() {
+            Some(item) => Some(item),
+            None => self.and_next(),
+        }
+    }
+}
+
+impl<'a> PageCursor for AndOnesHolePageCursor<'a> {
+    fn peek(&mut self) -> Option<PageItem> {
+        if self.pending.is_none() {
+            self.pending = self.and_next();
+        }
+
+        self.pending
+    }
+
+    fn advance_to(&mut self, page_idx: usize) {
+        if matches!(self.pending, Some((idx, _)) if idx >= page_idx) {
+            return;
+        }
+
+        self.pending = None;
+        self.zeroes.advance_to(page_idx);
+    }
+}
+
+// how a merge of two page streams combines pages... first (or second) is the side a page is missing from the other
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum MergeOp {
+    // zeroes hole | zeroes hole... single pages are kept
+    Or,
+    // ones hole & ones hole... single pages are kept
+    AndOnesHoles,
+    // ones hole | ones hole... single pages meet the other hole(1) and are dropped
+    OrOnesHoles,
+    // zeroes hole | ones hole
+    OrCross,
+    // zeroes hole ^ zeroes hole
+    Xor,
+    // zeroes hole ^ ones hole
+    XorCross,
+    // ones hole ^ ones hole... zeroes hole result
+    XorOnesHoles,
+    // zeroes hole & !zeroes hole
+    AndNot,
+    // zeroes hole & !ones hole
+    AndNotOnesHole,
+    // ones hole & !zeroes hole
+    OnesHoleAndNot,
+    // ones hole & !ones hole... zeroes hole result
+    AndNotOnesHoles,
+}
+
+impl MergeOp {
+    #[inline(always)]
+    fn apply(self, either: EitherOrBoth<PageItem, PageItem>) -> Option<PageItem> {
+        match self {
+            MergeOp::Or => match either {
+                EitherOrBoth::Both((idx, page_one), (_, page_two)) => Some((idx, page_one | page_two)),
+                EitherOrBoth::Left(page) | EitherOrBoth::Right(page) => Some(page),
+            },
+            MergeOp::AndOnesHoles => match either {
+                EitherOrBoth::Both((idx, page_one), (_, page_two)) => Some((idx, page_one & page_two)),
+                EitherOrBoth::Left(page) | EitherOrBoth::Right(page) => Some(page),
+            },
+            MergeOp::OrOnesHoles => match either {
+                // 1 | some => 1... where 1 is hole
+                EitherOrBoth::Both((idx, page_one), (_, page_two)) => Some((idx, page_one | page_two)),
+                EitherOrBoth::Left(_) | EitherOrBoth::Right(_) => None,
+            },
+            MergeOp::OrCross => or_merge_cross_types(either),
+            MergeOp::Xor => match either {
+                EitherOrBoth::Both((idx, page_one), (_, page_two)) => non_zero_page((idx, page_one ^ page_two)),
+                EitherOrBoth::Left(page) | EitherOrBoth::Right(page) => Some(page),
+            },
+            MergeOp::XorCross => Some(xor_merge_cross_types(either)),
+            MergeOp::XorOnesHoles => match either {
+                // 1 ^ some => !some... where 1 is hole
+                EitherOrBoth::Both((idx, page_one), (_, page_two)) => non_zero_page((idx, page_one ^ page_two)),
+                EitherOrBoth::Left((idx, page)) | EitherOrBoth::Right((idx, page)) => non_zero_page((idx, !page)),
+            },
+            MergeOp::AndNot => match either {
+                // some & !0 => some and 0 & !some => 0
+                EitherOrBoth::Both((idx, page_one), (_, page_two)) => non_zero_page((idx, page_one & !page_two)),
+                EitherOrBoth::Left(page) => Some(page),
+                EitherOrBoth::Right(_) => None,
+            },
+            MergeOp::AndNotOnesHole => match either {
+                // some & !1 => 0 and 0 & !some => 0
+                EitherOrBoth::Both((idx, page_one), (_, page_two)) => non_zero_page((idx, page_one & !page_two)),
+                EitherOrBoth::Left(_) | EitherOrBoth::Right(_) => None,
+            },
+            MergeOp::OnesHoleAndNot => match either {
+                // some & !0 => some and 1 & !some => !some
+                EitherOrBoth::Both((idx, page_one), (_, page_two)) => Some((idx, page_one & !page_two)),
+                EitherOrBoth::Left(page) => Some(page),
+                EitherOrBoth::Right((idx, page)) => Some((idx, !page)),
+            },
+            MergeOp::AndNotOnesHoles => match either {
+                // some & !1 => 0 and 1 & !some => !some
+                EitherOrBoth::Both((idx, page_one), (_, page_two)) => non_zero_page((idx, page_one & !page_two)),
+                EitherOrBoth::Left(_) => None,
+                EitherOrBoth::Right((idx, page)) => non_zero_page((idx, !page)),
+            },
+        }
+    }
+}
+
+// merge join of two cursors... result pages are at positions of input pages, so seeking seeks both sides
+pub(crate) struct MergePageCursor<'a> {
+    first: PeekedPageCursor<'a>,
+    second: PeekedPageCursor<'a>,
+    op: MergeOp,
+    pending: Option<PageItem>,
+}
+
+impl<'a> MergePageCursor<'a> {
+    pub(crate) fn new(first: AnyPageCursor<'a>, second: AnyPageCursor<'a>, op: MergeOp) -> MergePageCursor<'a> {
+        MergePageCursor {
+            first: PeekedPageCursor::new(first),
+            second: PeekedPageCursor::new(second),
+            op,
+            pending: None,
+        }
+    }
+}
+
+impl<'a> MergePageCursor<'a> {
+    // next result page straight from the inputs
+    #[inline]
+    fn merge_next(&mut self) -> Option<PageItem> {
+        loop {
+            let either = match (self.first.peek(), self.second.peek()) {
+                (None, None) => return None,
+                (Some(page_one), None) => {
+                    self.first.next();
+                    EitherOrBoth::Left(page_one)
+                }
+                (None, Some(page_two)) => {
+                    self.second.next();
+                    EitherOrBoth::Right(page_two)
+                }
+                (Some(page_one), Some(page_two)) => match page_one.0.cmp(&page_two.0) {
+                    Ordering::Less => {
+                        self.first.next();
+                        EitherOrBoth::Left(page_one)
+                    }
+                    Ordering::Greater => {
+                        self.second.next();
+                        EitherOrBoth::Right(page_two)
+                    }
+                    Ordering::Equal => {
+                        self.first.next();
+                        self.second.next();
+                        EitherOrBoth::Both(page_one, page_two)
+                    }
+                },
+            };
+
+            if let Some(item) = self.op.apply(either) {
+                return Some(item);
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for MergePageCursor<'a> {
+    type Item = PageItem;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.pending.take() {
+            Some(item) => Some(item),
+            None => self.merge_next(),
+        }
+    }
+}
+
+impl<'a> PageCursor for MergePageCursor<'a> {
+    fn peek(&mut self) -> Option<PageItem> {
+        if self.pending.is_none() {
+            self.pending = self.merge_next();
+        }
+
+        self.pending
+    }
+
+    fn advance_to(&mut self, page_idx: usize) {
+        if matches!(self.pending, Some((idx, _)) if idx >= page_idx) {
+            return;
+        }
+
+        self.pending = None;
+        self.first.advance_to(page_idx);
+        self.second.advance_to(page_idx);
+    }
+}
+
+// inverted pages within the universe
+pub(crate) struct NotPageCursor<'a> {
+    cursor: PeekedPageCursor<'a>,
+    universe_pages: usize,
+}
+
+impl<'a> NotPageCursor<'a> {
+    pub(crate) fn new(cursor: AnyPageCursor<'a>, universe_pages: usize) -> NotPageCursor<'a> {
+        NotPageCursor {
+            cursor: PeekedPageCursor::new(cursor),
+            universe_pages,
+        }
+    }
+
+    #[inline]
+    fn not(&self, item: Option<PageItem>) -> Option<PageItem> {
+        item.filter(|(page_idx, _)| *page_idx < self.universe_pages)
+            .map(|(page_idx, bit_page)| (page_idx, !bit_page))
+    }
+}
+
+impl<'a> Iterator for NotPageCursor<'a> {
+    type Item = PageItem;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.cursor.next();
+        self.not(item)
+    }
+}
+
+impl<'a> PageCursor for NotPageCursor<'a> {
+    fn peek(&mut self) -> Option<PageItem> {
+        let item = self.cursor.peek();
+        self.not(item)
+    }
+
+    fn advance_to(&mut self, page_idx: usize) {
+        self.cursor.advance_to(page_idx);
+    }
+}
+
+// pages within the universe with bits past it reset to zero
+pub(crate) struct MaskPageCursor<'a> {
+    cursor: PeekedPageCursor<'a>,
+    universe: Universe,
+}
+
+impl<'a> MaskPageCursor<'a> {
+    pub(crate) fn new(cursor: AnyPageCursor<'a>, universe: Universe) -> MaskPageCursor<'a> {
+        MaskPageCursor {
+            cursor: PeekedPageCursor::new(cursor),
+            universe,
+        }
+    }
+
+    #[inline]
+    fn mask(&self, item: Option<PageItem>) -> Option<PageItem> {
+        let universe = self.universe;
+
+        item.filter(|(page_idx, _)| *page_idx < universe.pages())
+            .map(|(page_idx, bit_page)| (page_idx, bit_page & universe.page_mask(page_idx)))
+    }
+}
+
+impl<'a> Iterator for MaskPageCursor<'a> {
+    type Item = PageItem;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.cursor.next();
+        self.mask(item)
+    }
+}
+
+impl<'a> PageCursor for MaskPageCursor<'a> {
+    fn peek(&mut self) -> Option<PageItem> {
+        let item = self.cursor.peek();
+        self.mask(item)
+    }
+
+    fn advance_to(&mut self, page_idx: usize) {
+        self.cursor.advance_to(page_idx);
+    }
+}
+
+// ones hole pages over a universe larger than the one of the input... hole(1) only covers the old universe, so pages
+// from its tail page onwards are materialized (with bits past it reset) and input pages past it are dropped
+pub(crate) struct ExtendOnesHolePageCursor<'a> {
+    cursor: PeekedPageCursor<'a>,
+    old: Universe,
+    // first page which may be materialized
+    page_idx: usize,
+    universe_pages: usize,
+}
+
+impl<'a> ExtendOnesHolePageCursor<'a> {
+    pub(crate) fn new(cursor: AnyPageCursor<'a>, old: Universe, universe: Universe) -> ExtendOnesHolePageCursor<'a> {
+        ExtendOnesHolePageCursor {
+            cursor: PeekedPageCursor::new(cursor),
+            old,
+            page_idx: old.last_bit_index().0,
+            universe_pages: universe.pages(),
+        }
+    }
+}
+
+impl<'a> Iterator for ExtendOnesHolePageCursor<'a> {
+    type Item = PageItem;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.peek()?;
+        if matches!(self.cursor.peek(), Some((idx, _)) if idx == item.0) {
+            self.cursor.next();
+        }
+        self.page_idx = max(self.page_idx, item.0 + 1);
+
+        Some(item)
+    }
+}
+
+impl<'a> PageCursor for ExtendOnesHolePageCursor<'a> {
+    fn peek(&mut self) -> Option<PageItem> {
+        // stored pages before the old tail page are kept as they are
+        let old_last_page = self.old.last_bit_index().0;
+        match self.cursor.peek() {
+            Some(item) if item.0 < old_last_page => return Some(item),
+            _ => {}
+        }
+
+        if self.page_idx >= self.universe_pages {
+            return None;
+        }
+
+        self.cursor.advance_to(self.page_idx);
+        let mask = self.old.page_mask(self.page_idx);
+        match self.cursor.peek() {
+            Some((idx, bit_page)) if idx == self.page_idx => Some((idx, bit_page & mask)),
+            _ => Some((self.page_idx, mask)),
+        }
+    }
+
+    fn advance_to(&mut self, page_idx: usize) {
+        self.cursor.advance_to(page_idx);
+        self.page_idx = max(self.page_idx, page_idx);
+    }
+}
+
+// every cursor a BitPageVecIter is made of... dispatched with a match instead of a virtual call per page
+pub(crate) enum AnyPageCursor<'a> {
+    Empty,
+    Slice(SlicePageCursor<&'a [BitPageWithPosition]>),
+    Runs(RunsPageCursor<&'a [BitRun]>),
+    Containers(ContainersPageCursor<&'a [Container]>),
+    // of vectors consumed by the stream
+    OwnedSlice(SlicePageCursor<Vec<BitPageWithPosition>>),
+    OwnedRuns(RunsPageCursor<Vec<BitRun>>),
+    OwnedContainers(ContainersPageCursor<Vec<Container>>),
+    // combinators hold their inputs inline... so each is boxed once, as a node of the tree
+    Merge(Box<MergePageCursor<'a>>),
+    And(Box<AndPageCursor<'a>>),
+    AndOnesHole(Box<AndOnesHolePageCursor<'a>>),
+    Not(Box<NotPageCursor<'a>>),
+    Mask(Box<MaskPageCursor<'a>>),
+    ExtendOnesHole(Box<ExtendOnesHolePageCursor<'a>>),
+    // arbitrary iterators and cursors from outside
+    Iter(IterPageCursor<'a>),
+    Dyn(PageCursorBox<'a>),
+}
+
+impl<'a> AnyPageCursor<'a> {
+    // boxed for outside use... leaves are boxed as they are, so the box is the only indirection
+    pub(crate) fn into_box(self) -> PageCursorBox<'a> {
+        match self {
+            AnyPageCursor::Slice(cursor) => Box::new(cursor),
+            AnyPageCursor::Runs(cursor) => Box::new(cursor),
+            AnyPageCursor::Containers(cursor) => Box::new(cursor),
+            AnyPageCursor::OwnedSlice(cursor) => Box::new(cursor),
+            AnyPageCursor::OwnedRuns(cursor) => Box::new(cursor),
+            AnyPageCursor::OwnedContainers(cursor) => Box::new(cursor),
+            AnyPageCursor::Dyn(cursor) => cursor,
+            cursor => Box::new(cursor),
+        }
+    }
+}
+
+macro_rules! dispatch {
+    ($cursor:expr, $inner:ident => $body:expr, $empty:expr) => {
+        match $cursor {
+            AnyPageCursor::Empty => $empty,
+            AnyPageCursor::Slice($inner) => $body,
+            AnyPageCursor::Runs($inner) => $body,
+            AnyPageCursor::Containers($inner) => $body,
+            AnyPageCursor::OwnedSlice($inner) => $body,
+            AnyPageCursor::OwnedRuns($inner) => $body,
+            AnyPageCursor::OwnedContainers($inner) => $body,
+            AnyPageCursor::Merge($inner) => $body,
+            AnyPageCursor::And($inner) => $body,
+            AnyPageCursor::AndOnesHole($inner) => $body,
+            AnyPageCursor::Not($inner) => $body,
+            AnyPageCursor::Mask($inner) => $body,
+            AnyPageCursor::ExtendOnesHole($inner) => $body,
+            AnyPageCursor::Iter($inner) => $body,
+            AnyPageCursor::Dyn($inner) => $body,
+        }
+    };
+}
+
+impl<'a> Iterator for AnyPageCursor<'a> {
+    type Item = PageItem;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        dispatch!(self, cursor => cursor.next(), None)
+    }
+}
+
+impl<'a> PageCursor for AnyPageCursor<'a> {
+    #[inline]
+    fn peek(&mut self) -> Option<PageItem> {
+        dispatch!(self, cursor => cursor.peek(), None)
+    }
+
+    fn advance_to(&mut self, page_idx: usize) {
+        dispatch!(self, cursor => cursor.advance_to(page_idx), ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::bit_page::join_bit_index;
+    use crate::bit_page_vec_containers::pages_to_containers;
+    use crate::bit_page_vec_cursor::{gallop, ContainersPageCursor, RunsPageCursor, SlicePageCursor};
+    use crate::bit_page_vec_fixtures::vectors;
+    use crate::bit_page_vec_iter::{BitPageVecIter, PageItem};
+    use crate::bit_page_vec_runs::{run_pages, BitRun};
+    use crate::{BitPage, BitPageVec, BooleanOp, PageCursor};
+
+    // seeks to every target and reads one page... same as skipping with a plain iterator
+    fn assert_seeks(mut cursor: impl PageCursor, pages: &[PageItem]) {
+        let mut position = 0;
+        for target in [0, 1, 1, 3, 17, 64, 65, 1000, 1024, 1500, 4000, 9000] {
+            cursor.advance_to(target);
+            position += pages[position..].partition_point(|(idx, _)| *idx < target);
+
+            assert_eq!(cursor.peek(), pages.get(position).copied(), "target={}", target);
+            assert_eq!(cursor.next(), pages.get(position).copied(), "target={}", target);
+            position = (position + 1).min(pages.len());
+        }
+    }
+
+    #[test]
+    fn test_gallop() {
+        let items = (0..100).map(|item| item * 2).collect_vec();
+
+        for from in [0, 1, 5, 50, 99, 100] {
+            for target in [0, 1, 2, 7, 100, 150, 198, 199, 500] {
+                let expected = from + items[from..].partition_point(|item| *item < target);
+                assert_eq!(gallop(&items, from, |item| *item < target), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cursors_advance_to() {
+        let bit_page_vec = BitPageVec::from_ranges(vec![0..10, 130..140, 1100..1200, 65_000..66_000, 500_000..500_001], (8000, 0));
+        let pages = bit_page_vec.pages.clone().unwrap();
+        let items = pages.iter().map(|page| (page.page_idx, page.bit_page)).collect_vec();
+
+        assert_seeks(SlicePageCursor::new(&pages), &items);
+
+        let containers = pages_to_containers(pages.clone());
+        assert_seeks(ContainersPageCursor::new(&containers), &items);
+
+        let runs = [(0, 10), (10, 3), (130, 10), (1100, 100), (1200, 20), (65_000, 1000), (500_000, 1)]
+            .iter()
+            .map(|(start, len)| BitRun { start: *start, len: *len })
+            .collect_vec();
+        assert_eq!(
+            RunsPageCursor::new(&runs).collect_vec(),
+            run_pages(runs.iter().copied()).collect_vec()
+        );
+        assert_seeks(RunsPageCursor::new(&runs), &run_pages(runs.iter().copied()).collect_vec());
+    }
+
+    #[test]
+    fn test_galloping_and() {
+        let small = BitPageVec::from_ranges(vec![64..67, 300_000..300_100, 4_000_000..4_000_001], (70_000, 0));
+        let large = BitPageVec::from_ranges(
+            (0..60_000u64).map(|page| page * 64 + page % 64..page * 64 + page % 64 + 2),
+            (70_000, 0),
+        );
+
+        let mut ones_hole = BitPageVec::all_ones((70_000, 0));
+        ones_hole.clear_range(300_050..300_060);
+
+        let expected = |first: &BitPageVec, second: &BitPageVec| first.iter().filter(|id| second.contains(*id)).collect_vec();
+
+        for (first, second) in [(&small, &large), (&large, &small), (&small, &ones_hole), (&ones_hole, &large)] {
+            let result = BitPageVecIter::and(first.page_iter(), second.page_iter()).into_bit_page_vec();
+            assert_eq!(result.iter().collect_vec(), expected(first, second));
+        }
+
+        // custom three way join... every cursor seeks to the largest pending page until all of them agree
+        let third = BitPageVec::from_ranges(vec![65..66, 300_048..300_050, 300_064..300_065], (70_000, 0));
+        let mut cursors = [
+            small.page_iter().into_cursor(),
+            large.page_iter().into_cursor(),
+            third.page_iter().into_cursor(),
+        ];
+
+        let mut ids = Vec::new();
+        loop {
+            let pending = cursors
+                .iter_mut()
+                .map(|cursor| cursor.peek().map(|(page_idx, _)| page_idx))
+                .collect::<Option<Vec<_>>>();
+            let page_idx = match pending.and_then(|pending| pending.into_iter().max()) {
+                Some(page_idx) => page_idx,
+                None => break,
+            };
+
+            cursors.iter_mut().for_each(|cursor| cursor.advance_to(page_idx));
+            if cursors.iter_mut().all(|cursor| cursor.peek().map(|(idx, _)| idx) == Some(page_idx)) {
+                let bit_page = cursors
+                    .iter_mut()
+                    .fold(BitPage::ones(), |bit_page, cursor| bit_page & cursor.next().unwrap().1);
+                ids.extend(BitPage::active_bits(bit_page).map(|bit_idx| join_bit_index(page_idx, bit_idx)));
+            }
+        }
+
+        assert_eq!(
+            ids,
+            expected(&small, &large).into_iter().filter(|id| third.contains(*id)).collect_vec()
+        );
+        assert_eq!(ids, vec![65, 300_048, 300_049]);
+    }
+
+    #[test]
+    fn test_merge_cursors() {
+        let all_kinds = vectors((200, 0));
+        let (sparse, runs, containers, ones_hole) = (&all_kinds[2], &all_kinds[3], &all_kinds[4], &all_kinds[5]);
+        let vectors = [runs, containers, sparse, ones_hole];
+
+        // deep op over every kind... same as evaluating it id by id
+        let leaf = |index: usize| BooleanOp::new_leaf_op(vectors[index]);
+        let op = BooleanOp::new_or_op(vec![
+            BooleanOp::new_and_op(vec![leaf(0), leaf(2), BooleanOp::new_not_op(leaf(1))]).unwrap(),
+            BooleanOp::new_and_op(vec![leaf(3), BooleanOp::new_or_op(vec![leaf(1), leaf(2)]).unwrap()]).unwrap(),
+            BooleanOp::new_not_op(BooleanOp::new_or_op(vec![leaf(0), leaf(3), leaf(2)]).unwrap()),
+        ])
+        .unwrap();
+
+        let expected = (0..12_800u64)
+            .filter(|id| {
+                let contains = |index: usize| vectors[index].contains(*id);
+                (contains(0) && contains(2) && !contains(1))
+                    || (contains(3) && (contains(1) || contains(2)))
+                    || !(contains(0) || contains(3) || contains(2))
+            })
+            .collect_vec();
+        assert_eq!(op.evaluate().into_bit_page_vec().iter().collect_vec(), expected);
+
+        // merged (zeroes hole) cursors seek their inputs
+        for (first, second) in [(runs, sparse), (containers, runs), (sparse, containers)] {
+            let merged = || BitPageVecIter::or(first.page_iter(), second.page_iter());
+            let items = merged().into_cursor().collect_vec();
+            assert_seeks(merged().into_cursor(), &items);
+
+            let xor = || BitPageVecIter::xor(first.page_iter(), second.page_iter());
+            let items = xor().into_cursor().collect_vec();
+            assert_seeks(xor().into_cursor(), &items);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use std::hash::{Hash, Hasher};
+
+// @author shailendra.sharma
+use crate::bit_page_vec_iter::BitPageVecIter;
+use crate::BitPageVec;
+
+// equality is on logical content within last_bit_index... kind and stored pages do not matter
+impl PartialEq for BitPageVec {
+    fn eq(&self, other: &BitPageVec) -> bool {
+        self.last_bit_index == other.last_bit_index && !BitPageVecIter::xor(self.page_iter(), other.page_iter()).has_active_bits()
+    }
+}
+
+impl Eq for BitPageVec {}
+
+impl Hash for BitPageVec {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.last_bit_index.hash(state);
+
+        // dense vectors hash their (sparse) complement... so hashing stays cheap for either hole
+        let dense = self.active_bits_count() as u64 * 2 > self.universe_len();
+        dense.hash(state);
+
+        let iter = if dense { self.page_iter().not() } else { self.page_iter() };
+        for (page_idx, bit_page) in iter.into_active_pages() {
+            page_idx.hash(state);
+            bit_page.hash(state);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::HashSet;
+    use std::hash::{Hash, Hasher};
+
+    use crate::bit_page_vec::BitPageVecKind;
+    use crate::bit_page_vec_iter::BitPageVecIter;
+    use crate::BitPageVec;
+
+    fn hash(bit_page_vec: &BitPageVec) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        bit_page_vec.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_eq_across_kinds() {
+        let last_bit_index = (20, 5);
+
+        let all_ones = BitPageVec::all_ones(last_bit_index);
+        let empty_ones_hole = BitPageVec::new(BitPageVecKind::SparseWithOnesHole, Some(Vec::new()), last_bit_index);
+        let mut full_zeroes_hole = BitPageVec::all_zeros(last_bit_index);
+        full_zeroes_hole.extend(0..(20 * 64 + 5));
+
+        assert!(matches!(full_zeroes_hole.kind(), BitPageVecKind::SparseWithZeroesHole));
+        assert_eq!(all_ones, empty_ones_hole);
+        assert_eq!(all_ones, full_zeroes_hole);
+        assert_eq!(hash(&all_ones), hash(&empty_ones_hole));
+        assert_eq!(hash(&all_ones), hash(&full_zeroes_hole));
+
+        // same ids... stored as zeroes hole pages and as ones hole pages
+        let mut zeroes_hole = BitPageVec::all_zeros(last_bit_index);
+        zeroes_hole.extend((0..1000).filter(|id| id % 10 != 0));
+        let mut ones_hole = BitPageVec::all_ones(last_bit_index);
+        ones_hole.clear_range(1000..1400);
+        for id in (0..1000).step_by(10) {
+            ones_hole.remove(id);
+        }
+        let pages = zeroes_hole.pages.clone().unwrap();
+        let compacted = BitPageVecIter::compact_sparse_with_zeroes_hole(pages, last_bit_index.into());
+
+        assert_eq!(zeroes_hole, ones_hole);
+        assert_eq!(zeroes_hole, compacted);
+        assert_eq!(hash(&zeroes_hole), hash(&ones_hole));
+
+        let set = vec![zeroes_hole.clone(), ones_hole, compacted].into_iter().collect::<HashSet<_>>();
+        assert_eq!(set.len(), 1);
+
+        zeroes_hole.remove(1);
+        assert!(!set.contains(&zeroes_hole));
+        assert_ne!(BitPageVec::all_zeros(last_bit_index), BitPageVec::all_zeros((20, 6)));
+    }
+}
@@ -0,0 +1,97 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+// @author shailendra.sharma
+use crate::bit_page_vec::BitPageVecKind;
+use crate::{BitPageVec, GrowFill, Universe};
+
+// kinds of vectors (in order) returned by vectors()
+pub(crate) const KINDS: [BitPageVecKind; 6] = [
+    BitPageVecKind::AllZeroes,
+    BitPageVecKind::AllOnes,
+    BitPageVecKind::SparseWithZeroesHole,
+    BitPageVecKind::Runs,
+    BitPageVecKind::Containers,
+    BitPageVecKind::SparseWithOnesHole,
+];
+
+// one vector of every kind over the same universe (of at least a few pages)... built only through public constructors
+pub(crate) fn vectors<U: Into<Universe>>(universe: U) -> Vec<BitPageVec> {
+    let universe = universe.into();
+    let (len, last_bit_index) = (universe.len(), universe.last_bit_index());
+
+    let mut ones_hole = BitPageVec::all_ones(last_bit_index);
+    ones_hole.clear_range(len / 4..len / 3);
+    ones_hole.remove(len / 2);
+    ones_hole.remove(len - 2);
+
+    vec![
+        BitPageVec::all_zeros(last_bit_index),
+        BitPageVec::all_ones(last_bit_index),
+        collect((0..len).filter(|id| id % 3 == 0), universe),
+        collect(len / 8..len - len / 8, universe),
+        collect((0..len).filter(|id| id % 301 == 0).chain(Some(len - 1)), universe),
+        ones_hole,
+    ]
+}
+
+// collected vectors end at their largest id... so they are grown to universe
+pub(crate) fn collect<I: IntoIterator<Item = u64>>(ids: I, universe: Universe) -> BitPageVec {
+    let mut bit_page_vec = ids.into_iter().collect::<BitPageVec>();
+    bit_page_vec.grow_to(universe.last_bit_index(), GrowFill::Zeroes);
+    bit_page_vec
+}
+
+// counts allocations per thread... so tests running in parallel do not see each other's
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+// result of f along with the number of allocations (and reallocations) it made on this thread
+pub(crate) fn allocations<R, F: FnOnce() -> R>(f: F) -> (R, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+
+    (result, ALLOCATIONS.with(Cell::get) - before)
+}
+
+#[test]
+fn test_vectors() {
+    for universe in [(12, 17), (20, 7), (40, 9), (200, 0)] {
+        let vectors = vectors(universe);
+        for (bit_page_vec, kind) in vectors.iter().zip(KINDS.iter()) {
+            assert_eq!(bit_page_vec.kind(), kind, "{:?}", universe);
+            assert_eq!(bit_page_vec.universe(), Universe::from(universe));
+        }
+    }
+}
+
+#[test]
+fn test_allocations() {
+    let (ids, count) = allocations(|| (0..100u64).collect::<Vec<_>>());
+    assert_eq!(ids.len(), 100);
+    assert_eq!(count, 1);
+
+    assert_eq!(allocations(|| 7).1, 0);
+}
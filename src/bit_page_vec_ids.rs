@@ -0,0 +1,309 @@
+use std::iter::FromIterator;
+
+// @author shailendra.sharma
+use crate::bit_page::{join_bit_index, split_bit_index, BitPageWithPosition};
+use crate::bit_page_active_bits::BitPageActiveBitsIterator;
+use crate::bit_page_vec_iter::{ActivePages, BitPageVecIter};
+use crate::bit_page_vec_resize::GrowFill;
+use crate::{BitPage, BitPageVec, Universe};
+
+// pages are decoded one word at a time into ids... nothing is allocated per page
+pub enum BitPageVecIdsIterator<'a> {
+    None,
+    Some {
+        pages: ActivePages<'a>,
+        // bits of the current page which are not yet out
+        page_idx: usize,
+        bits: BitPageActiveBitsIterator,
+        // pages come in descending order and bits from the highest
+        rev: bool,
+    },
+}
+
+impl<'a> BitPageVecIdsIterator<'a> {
+    pub(crate) fn from_pages<P: Into<ActivePages<'a>>>(pages: P) -> BitPageVecIdsIterator<'a> {
+        BitPageVecIdsIterator::Some {
+            pages: pages.into(),
+            page_idx: 0,
+            bits: BitPage::active_bits(BitPage::zeroes()),
+            rev: false,
+        }
+    }
+
+    // pages must be in descending order... ids come out from the highest to the lowest
+    pub(crate) fn from_pages_rev<P: Into<ActivePages<'a>>>(pages: P) -> BitPageVecIdsIterator<'a> {
+        BitPageVecIdsIterator::Some {
+            pages: pages.into(),
+            page_idx: 0,
+            bits: BitPage::active_bits(BitPage::zeroes()),
+            rev: true,
+        }
+    }
+
+    /// fills buf with the next ids (in the order of the iterator)... returns how many were written, so 0 means there
+    /// are no more ids (or buf is empty)
+    pub fn decode_into(&mut self, buf: &mut [u64]) -> usize {
+        match self {
+            BitPageVecIdsIterator::None => 0,
+            BitPageVecIdsIterator::Some {
+                pages,
+                page_idx,
+                bits,
+                rev,
+            } => {
+                let mut len = 0;
+                while len < buf.len() {
+                    let base = join_bit_index(*page_idx, 0);
+                    len += if *rev {
+                        bits.decode_rev_into(base, &mut buf[len..])
+                    } else {
+                        bits.decode_into(base, &mut buf[len..])
+                    };
+
+                    if bits.len() == 0 {
+                        match pages.next() {
+                            Some((next_page_idx, bit_page)) => {
+                                *page_idx = next_page_idx;
+                                *bits = BitPage::active_bits(bit_page);
+                            }
+                            None => break,
+                        }
+                    }
+                }
+
+                len
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for BitPageVecIdsIterator<'a> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            BitPageVecIdsIterator::None => None,
+            BitPageVecIdsIterator::Some {
+                pages,
+                page_idx,
+                bits,
+                rev,
+            } => loop {
+                let bit_idx = if *rev { bits.next_back() } else { bits.next() };
+                if let Some(bit_idx) = bit_idx {
+                    return Some(join_bit_index(*page_idx, bit_idx));
+                }
+
+                let (next_page_idx, bit_page) = pages.next()?;
+                *page_idx = next_page_idx;
+                *bits = BitPage::active_bits(bit_page);
+            },
+        }
+    }
+}
+
+impl BitPageVec {
+    /// ids past the universe are dropped unless auto_grow is on (see set_bit)
+    #[inline]
+    pub fn insert(&mut self, index: u64) {
+        let (page_idx, bit_idx) = split_bit_index(index);
+        self.set_bit(page_idx, bit_idx);
+    }
+
+    #[inline]
+    pub fn remove(&mut self, index: u64) {
+        let (page_idx, bit_idx) = split_bit_index(index);
+        self.clear_bit(page_idx, bit_idx);
+    }
+
+    #[inline]
+    pub fn contains(&self, index: u64) -> bool {
+        let (page_idx, bit_idx) = split_bit_index(index);
+        self.is_bit_set(page_idx, bit_idx)
+    }
+
+    /// ids of all active bits (within last_bit_index) in ascending order
+    pub fn iter(&self) -> BitPageVecIdsIterator<'_> {
+        BitPageVecIdsIterator::from_pages(self.page_iter().into_active_pages())
+    }
+}
+
+impl FromIterator<u64> for BitPageVec {
+    // last bit index is derived from the largest id
+    fn from_iter<T: IntoIterator<Item = u64>>(iter: T) -> Self {
+        let mut ids = iter.into_iter().collect::<Vec<_>>();
+        ids.sort_unstable();
+        ids.dedup();
+
+        let last_bit_index = ids.last().map_or((0, 0), |last| split_bit_index(last.saturating_add(1)));
+
+        BitPageVecIter::compact_sparse_with_zeroes_hole(pages_from_sorted_ids(ids), Universe::from(last_bit_index))
+    }
+}
+
+impl Extend<u64> for BitPageVec {
+    // universe grows to the largest id (same as from_iter)... so no id is dropped
+    fn extend<T: IntoIterator<Item = u64>>(&mut self, iter: T) {
+        let ids = iter.into_iter().collect::<Vec<_>>();
+        if let Some(last) = ids.iter().max() {
+            self.grow_to(split_bit_index(last.saturating_add(1)), GrowFill::Zeroes);
+        }
+
+        for index in ids {
+            self.insert(index);
+        }
+    }
+}
+
+impl IntoIterator for BitPageVec {
+    type Item = u64;
+    type IntoIter = BitPageVecIdsIterator<'static>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        BitPageVecIdsIterator::from_pages(self.into_page_iter().into_active_pages())
+    }
+}
+
+impl<'a> IntoIterator for &'a BitPageVec {
+    type Item = u64;
+    type IntoIter = BitPageVecIdsIterator<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// ids must be sorted and de-duplicated
+pub(crate) fn pages_from_sorted_ids<I>(ids: I) -> Vec<BitPageWithPosition>
+where
+    I: IntoIterator<Item = u64>,
+{
+    let mut pages: Vec<BitPageWithPosition> = Vec::new();
+
+    for index in ids {
+        let (page_idx, bit_idx) = split_bit_index(index);
+        match pages.last_mut() {
+            Some(page) if page.page_idx == page_idx => BitPage::set_bit(&mut page.bit_page, bit_idx),
+            _ => {
+                let mut bit_page = BitPage::zeroes();
+                BitPage::set_bit(&mut bit_page, bit_idx);
+
+                pages.push(BitPageWithPosition { page_idx, bit_page });
+            }
+        }
+    }
+
+    pages
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::{BitPageVec, BitPageVecIdsIterator};
+
+    #[test]
+    fn test_insert_remove_contains() {
+        let mut bit_page_vec = BitPageVec::all_zeros((10, 0));
+
+        for id in &[0, 63, 64, 65, 639] {
+            bit_page_vec.insert(*id);
+        }
+
+        assert!(bit_page_vec.contains(63));
+        assert!(bit_page_vec.contains(64));
+        assert!(!bit_page_vec.contains(66));
+
+        bit_page_vec.remove(64);
+        assert!(!bit_page_vec.contains(64));
+
+        assert_eq!(bit_page_vec.iter().collect_vec(), vec![0, 63, 65, 639]);
+    }
+
+    #[test]
+    fn test_from_iter_and_into_iter() {
+        let ids = vec![700, 3, 64, 3, 128];
+        let bit_page_vec = ids.into_iter().collect::<BitPageVec>();
+
+        assert_eq!(bit_page_vec.last_bit_index(), (10, 61));
+        assert_eq!((&bit_page_vec).into_iter().collect_vec(), vec![3, 64, 128, 700]);
+
+        let mut extended = bit_page_vec.clone();
+        extended.extend(vec![4, 5]);
+        assert_eq!(extended.into_iter().collect_vec(), vec![3, 4, 5, 64, 128, 700]);
+
+        // extend past the universe grows it... insert past it is dropped
+        let mut extended = bit_page_vec.clone();
+        extended.extend(vec![5000, 4]);
+        assert_eq!(extended.last_bit_index(), (78, 9));
+        assert!(extended.contains(5000));
+        assert_eq!(extended.iter().collect_vec(), vec![3, 4, 64, 128, 700, 5000]);
+
+        let mut inserted = bit_page_vec.clone();
+        inserted.insert(5000);
+        assert!(!inserted.contains(5000));
+        assert_eq!(inserted, bit_page_vec);
+
+        // a ones hole grows with zeroes
+        let mut all_ones = BitPageVec::all_ones((1, 0));
+        all_ones.extend(vec![100]);
+        assert_eq!(all_ones.iter().collect_vec(), (0..64).chain(vec![100]).collect_vec());
+    }
+
+    #[test]
+    fn test_iter_all_ones() {
+        let bit_page_vec = BitPageVec::all_ones((1, 2));
+        assert_eq!(bit_page_vec.iter().count(), 66);
+        assert_eq!(bit_page_vec.iter().last(), Some(65));
+    }
+
+    #[test]
+    fn test_decode_into() {
+        let mut bit_page_vec = BitPageVec::all_zeros((40, 0));
+        bit_page_vec.set_range(100..260);
+        for id in &[0, 1, 63, 64, 1000, 2559] {
+            bit_page_vec.insert(*id);
+        }
+
+        let decode = |mut iter: BitPageVecIdsIterator<'_>, buf_len: usize| {
+            let (mut buf, mut ids) = (vec![0u64; buf_len], vec![]);
+            loop {
+                let len = iter.decode_into(&mut buf);
+                if len == 0 {
+                    break;
+                }
+
+                ids.extend_from_slice(&buf[..len]);
+            }
+
+            ids
+        };
+
+        for buf_len in [1, 3, 64, 100, 1000] {
+            assert_eq!(
+                decode(bit_page_vec.iter(), buf_len),
+                bit_page_vec.iter().collect_vec(),
+                "buf_len={}",
+                buf_len
+            );
+            assert_eq!(
+                decode(bit_page_vec.iter_rev(), buf_len),
+                bit_page_vec.iter_rev().collect_vec(),
+                "buf_len={}",
+                buf_len
+            );
+        }
+
+        // decoding can be mixed with plain iteration
+        let mut iter = bit_page_vec.iter();
+        assert_eq!(iter.next(), Some(0));
+        let mut buf = [0u64; 3];
+        assert_eq!(iter.decode_into(&mut buf), 3);
+        assert_eq!(buf, [1, 63, 64]);
+        assert_eq!(iter.next(), Some(100));
+
+        assert_eq!(decode(BitPageVec::all_zeros((1, 0)).iter(), 8), Vec::<u64>::new());
+        assert_eq!(decode(BitPageVec::all_ones((1, 2)).iter(), 8).len(), 66);
+        assert_eq!(bit_page_vec.iter().decode_into(&mut []), 0);
+    }
+}
@@ -1,45 +1,186 @@
 use std::cmp::{min, Ordering};
 use std::fmt;
-use std::iter::empty;
 
 use itertools::{EitherOrBoth, Itertools};
 
-use crate::{BitPage, BitPageVec};
+use crate::compaction_policy::DEFAULT_COMPACTION_POLICY;
+use crate::{BitPage, BitPageVec, CompactionPolicy, CompactionStats, Universe, UniversePolicy};
 // @author shailendra.sharma
 use crate::bit_page::BitPageWithPosition;
 use crate::bit_page_vec::BitPageVecKind;
+use crate::bit_page_vec_containers::pages_to_containers;
+use crate::bit_page_vec_cursor::{
+    AndOnesHolePageCursor, AndPageCursor, AnyPageCursor, ContainersPageCursor, ExtendOnesHolePageCursor, IterPageCursor, MaskPageCursor, MergeOp, MergePageCursor,
+    NotPageCursor, PageCursor, PageCursorBox, RunsPageCursor, SlicePageCursor,
+};
+use crate::bit_page_vec_runs::{count_runs, pages_to_runs};
 
 // use std::time::Instant;
 
 pub type PageItem = (usize, u64);
 pub type PageIterator<'a> = Box<dyn Iterator<Item = PageItem> + 'a>;
 
+// kind of a stream... pages of Runs and Containers vectors are streamed as zeroes hole pages
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum StreamKind {
+    AllZeroes,
+    ZeroesHole,
+    AllOnes,
+    OnesHole,
+}
+
+impl StreamKind {
+    fn kind(self) -> &'static BitPageVecKind {
+        match self {
+            StreamKind::AllZeroes => &BitPageVecKind::AllZeroes,
+            StreamKind::ZeroesHole => &BitPageVecKind::SparseWithZeroesHole,
+            StreamKind::AllOnes => &BitPageVecKind::AllOnes,
+            StreamKind::OnesHole => &BitPageVecKind::SparseWithOnesHole,
+        }
+    }
+}
+
+impl From<BitPageVecKind> for StreamKind {
+    fn from(kind: BitPageVecKind) -> Self {
+        match kind {
+            BitPageVecKind::AllZeroes => StreamKind::AllZeroes,
+            BitPageVecKind::SparseWithZeroesHole | BitPageVecKind::Runs | BitPageVecKind::Containers => StreamKind::ZeroesHole,
+            BitPageVecKind::AllOnes => StreamKind::AllOnes,
+            BitPageVecKind::SparseWithOnesHole => StreamKind::OnesHole,
+        }
+    }
+}
+
+/// pages having at least one active bit in ascending page order (or as they came, see From<PageIterator>)
+pub struct ActivePages<'a>(Pages<'a>);
+
+enum Pages<'a> {
+    // of a stream... holes are materialized and pages are masked to the universe
+    Stream {
+        kind: StreamKind,
+        cursor: AnyPageCursor<'a>,
+        universe: Universe,
+        // next page of the universe (ones holes only)
+        page_idx: usize,
+    },
+    // pages which are known to be active already
+    Iter(PageIterator<'a>),
+}
+
+impl<'a> From<PageIterator<'a>> for ActivePages<'a> {
+    fn from(iter: PageIterator<'a>) -> Self {
+        ActivePages(Pages::Iter(iter))
+    }
+}
+
+impl<'a> Iterator for ActivePages<'a> {
+    type Item = PageItem;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (kind, cursor, universe, next_page_idx) = match &mut self.0 {
+            Pages::Stream {
+                kind,
+                cursor,
+                universe,
+                page_idx,
+            } => (kind, cursor, *universe, page_idx),
+            Pages::Iter(iter) => return iter.next(),
+        };
+
+        let universe_pages = universe.pages();
+        loop {
+            let (page_idx, bit_page) = match kind {
+                StreamKind::AllZeroes => return None,
+                StreamKind::ZeroesHole => match cursor.next() {
+                    Some((page_idx, bit_page)) if page_idx < universe_pages => (page_idx, bit_page),
+                    _ => {
+                        // nothing past the universe is wanted... later calls need not reach the cursor again
+                        *kind = StreamKind::AllZeroes;
+                        return None;
+                    }
+                },
+                StreamKind::AllOnes | StreamKind::OnesHole => {
+                    if *next_page_idx >= universe_pages {
+                        return None;
+                    }
+
+                    // pages which are not stored are the hole
+                    let page_idx = *next_page_idx;
+                    *next_page_idx += 1;
+                    cursor.advance_to(page_idx);
+                    match cursor.peek() {
+                        Some((idx, bit_page)) if idx == page_idx => {
+                            cursor.next();
+                            (page_idx, bit_page)
+                        }
+                        _ => (page_idx, BitPage::ones()),
+                    }
+                }
+            };
+
+            let bit_page = bit_page & universe.page_mask(page_idx);
+            if !BitPage::is_zeroes(&bit_page) {
+                return Some((page_idx, bit_page));
+            }
+        }
+    }
+}
+
 pub struct BitPageVecIter<'a> {
-    kind: BitPageVecKind,
-    iter: PageIterator<'a>,
-    last_bit_index: (usize, usize),
+    kind: StreamKind,
+    iter: AnyPageCursor<'a>,
+    universe: Universe,
 }
 
 impl<'a> fmt::Debug for BitPageVecIter<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "BitPageVecIter(kind={:?})", self.kind)
+        write!(f, "BitPageVecIter(kind={:?})", self.kind())
     }
 }
 
 impl<'a> BitPageVecIter<'a> {
-    pub fn new(kind: BitPageVecKind, iter: PageIterator, last_bit_index: (usize, usize)) -> BitPageVecIter {
+    /// Runs and Containers kinds are taken as SparseWithZeroesHole (their pages have a zeroes hole)
+    pub fn new<U: Into<Universe>>(kind: BitPageVecKind, iter: PageIterator<'a>, universe: U) -> BitPageVecIter<'a> {
+        BitPageVecIter::with_cursor(kind, AnyPageCursor::Iter(IterPageCursor::new(iter)), universe)
+    }
+
+    /// stream over a seekable cursor... joins with it skip pages instead of stepping through them
+    pub fn from_cursor<U: Into<Universe>>(kind: BitPageVecKind, cursor: PageCursorBox<'a>, universe: U) -> BitPageVecIter<'a> {
+        BitPageVecIter::with_cursor(kind, AnyPageCursor::Dyn(cursor), universe)
+    }
+
+    // stored pages (ascending and with no duplicates) of a sparse vector of kind
+    pub(crate) fn from_pages<U: Into<Universe>>(kind: BitPageVecKind, pages: Vec<BitPageWithPosition>, universe: U) -> BitPageVecIter<'a> {
+        BitPageVecIter::with_cursor(kind, AnyPageCursor::OwnedSlice(SlicePageCursor::new(pages)), universe)
+    }
+
+    pub(crate) fn with_cursor<U: Into<Universe>>(kind: BitPageVecKind, cursor: AnyPageCursor<'a>, universe: U) -> BitPageVecIter<'a> {
         BitPageVecIter {
-            kind,
-            iter,
-            last_bit_index,
+            kind: StreamKind::from(kind),
+            iter: cursor,
+            universe: universe.into(),
         }
     }
 
+    /// raw stored pages as a seekable cursor (for custom joins)... holes are as per kind and pages are not masked to the universe
+    pub fn into_cursor(self) -> PageCursorBox<'a> {
+        self.iter.into_box()
+    }
+
     pub fn kind(&self) -> &BitPageVecKind {
-        &self.kind
+        self.kind.kind()
+    }
+
+    pub fn universe(&self) -> Universe {
+        self.universe
     }
 
     pub fn into_bit_page_vec(self) -> BitPageVec {
+        self.into_bit_page_vec_with(&DEFAULT_COMPACTION_POLICY)
+    }
+
+    /// same as into_bit_page_vec... but pages are compacted the way policy decides
+    pub fn into_bit_page_vec_with(self, policy: &dyn CompactionPolicy) -> BitPageVec {
         // let instant = Instant::now();
         // let kind = self.kind;
 
@@ -47,12 +188,18 @@ impl<'a> BitPageVecIter<'a> {
         //     debug!(target: "bit_page_vec_log", "into_bit_page_vec(kind={:?})", self.kind);
         // }
 
+        // pages past the universe are dropped and tail bits are reset to the hole
+        let universe = self.universe;
+        let universe_pages = universe.pages();
+
         let result = match self.kind {
-            BitPageVecKind::AllZeroes => BitPageVec::all_zeros(self.last_bit_index),
-            BitPageVecKind::SparseWithZeroesHole => {
+            StreamKind::AllZeroes => BitPageVec::all_zeros(universe),
+            StreamKind::ZeroesHole => {
                 let pages = self
                     .iter
+                    .take_while(|(page_idx, _)| *page_idx < universe_pages)
                     .filter_map(|(page_idx, bit_page)| {
+                        let bit_page = bit_page & universe.page_mask(page_idx);
                         if BitPage::is_zeroes(&bit_page) {
                             None
                         } else {
@@ -61,13 +208,15 @@ impl<'a> BitPageVecIter<'a> {
                     })
                     .collect_vec();
 
-                Self::compact_sparse_with_zeroes_hole(pages, self.last_bit_index)
+                Self::compact_pages(pages, BitPage::zeroes(), universe, policy)
             }
-            BitPageVecKind::AllOnes => BitPageVec::all_ones(self.last_bit_index),
-            BitPageVecKind::SparseWithOnesHole => {
+            StreamKind::AllOnes => BitPageVec::all_ones(universe),
+            StreamKind::OnesHole => {
                 let pages = self
                     .iter
+                    .take_while(|(page_idx, _)| *page_idx < universe_pages)
                     .filter_map(|(page_idx, bit_page)| {
+                        let bit_page = bit_page | !universe.page_mask(page_idx);
                         if BitPage::is_ones(&bit_page) {
                             None
                         } else {
@@ -76,7 +225,7 @@ impl<'a> BitPageVecIter<'a> {
                     })
                     .collect_vec();
 
-                Self::compact_sparse_with_ones_hole(pages, self.last_bit_index)
+                Self::compact_pages(pages, BitPage::ones(), universe, policy)
             }
         };
 
@@ -87,100 +236,126 @@ impl<'a> BitPageVecIter<'a> {
         result
     }
 
+    /// same stream over another universe... a smaller universe drops bits past it and a larger one adds zeroes
+    pub fn with_universe<U: Into<Universe>>(self, universe: U) -> BitPageVecIter<'a> {
+        let universe = universe.into();
+        if universe <= self.universe {
+            // bits past the universe are masked by whoever consumes the stream
+            return BitPageVecIter { universe, ..self };
+        }
+
+        let old = self.universe;
+
+        match self.kind {
+            StreamKind::AllZeroes => BitPageVecIter { universe, ..self },
+            StreamKind::ZeroesHole => {
+                // bits stored past the old universe must not show up in the extended one
+                let cursor = MaskPageCursor::new(self.iter, old);
+
+                BitPageVecIter::with_cursor(BitPageVecKind::SparseWithZeroesHole, AnyPageCursor::Mask(Box::new(cursor)), universe)
+            }
+            StreamKind::AllOnes | StreamKind::OnesHole => {
+                // pages from the old tail page onwards are materialized... hole(1) only covers the old universe
+                let cursor = ExtendOnesHolePageCursor::new(self.iter, old, universe);
+
+                BitPageVecIter::with_cursor(BitPageVecKind::SparseWithOnesHole, AnyPageCursor::ExtendOnesHole(Box::new(cursor)), universe)
+            }
+        }
+    }
+
+    /// brings both streams to the same universe as per policy
+    pub fn align(
+        first: BitPageVecIter<'a>,
+        second: BitPageVecIter<'a>,
+        policy: UniversePolicy,
+    ) -> (BitPageVecIter<'a>, BitPageVecIter<'a>) {
+        if first.universe == second.universe {
+            return (first, second);
+        }
+
+        let universe = first.universe.combine(second.universe, policy);
+
+        (first.with_universe(universe), second.with_universe(universe))
+    }
+
+    // stream of pages having at least one active bit... holes are materialized and pages are masked to the universe
+    pub(crate) fn into_active_pages(self) -> ActivePages<'a> {
+        ActivePages(Pages::Stream {
+            kind: self.kind,
+            cursor: self.iter,
+            universe: self.universe,
+            page_idx: 0,
+        })
+    }
+
+    // pages past the universe are dropped... inverted tail bits are masked by whoever consumes the stream
+    #[allow(clippy::should_implement_trait)]
     pub fn not(self) -> BitPageVecIter<'a> {
+        let universe_pages = self.universe.pages();
+
         match self.kind {
-            BitPageVecKind::AllZeroes => BitPageVec::all_ones(self.last_bit_index).into_iter(),
-            BitPageVecKind::SparseWithZeroesHole => BitPageVecIter::new(
+            StreamKind::AllZeroes => BitPageVec::all_ones(self.universe).into_page_iter(),
+            StreamKind::ZeroesHole => BitPageVecIter::with_cursor(
                 BitPageVecKind::SparseWithOnesHole,
-                Box::new(self.iter.map(|(page_idx, bit_page)| (page_idx, !bit_page))),
-                self.last_bit_index,
+                AnyPageCursor::Not(Box::new(NotPageCursor::new(self.iter, universe_pages))),
+                self.universe,
             ),
-            BitPageVecKind::AllOnes => BitPageVec::all_zeros(self.last_bit_index).into_iter(),
-            BitPageVecKind::SparseWithOnesHole => BitPageVecIter::new(
+            StreamKind::AllOnes => BitPageVec::all_zeros(self.universe).into_page_iter(),
+            StreamKind::OnesHole => BitPageVecIter::with_cursor(
                 BitPageVecKind::SparseWithZeroesHole,
-                Box::new(self.iter.map(|(page_idx, bit_page)| (page_idx, !bit_page))),
-                self.last_bit_index,
+                AnyPageCursor::Not(Box::new(NotPageCursor::new(self.iter, universe_pages))),
+                self.universe,
             ),
         }
     }
 
     pub fn or(first: BitPageVecIter<'a>, second: BitPageVecIter<'a>) -> BitPageVecIter<'a> {
+        BitPageVecIter::or_with(first, second, UniversePolicy::default())
+    }
+
+    /// same as or... different universes are combined as per policy
+    pub fn or_with(first: BitPageVecIter<'a>, second: BitPageVecIter<'a>, policy: UniversePolicy) -> BitPageVecIter<'a> {
+        let (first, second) = BitPageVecIter::align(first, second, policy);
+
         // if log_enabled!(target: "bit_page_vec_log", Level::Debug) {
         //     debug!(target: "bit_page_vec_log", "BitPageVecIter::OR first={:?} second={:?}", first, second);
         // }
 
         let result = match first.kind {
-            BitPageVecKind::AllZeroes => second,
-            BitPageVecKind::SparseWithZeroesHole => match second.kind {
-                BitPageVecKind::AllZeroes => first,
-                BitPageVecKind::SparseWithZeroesHole => {
+            StreamKind::AllZeroes => second,
+            StreamKind::ZeroesHole => match second.kind {
+                StreamKind::AllZeroes => first,
+                StreamKind::ZeroesHole => {
                     // merge here... same type with zeroes hole
                     // 0 | 0 => 0
                     // some | 0 => some
                     // 0 | some => some
                     // some | some => or(some)
-                    let iter = first.iter.merge_join_by(second.iter, merge_cmp).map(|either| match either {
-                        EitherOrBoth::Both((idx_1, mut page_one), (_idx_2, page_two)) => {
-                            page_one |= page_two;
-
-                            (idx_1, page_one)
-                        }
-                        EitherOrBoth::Left((idx, page)) | EitherOrBoth::Right((idx, page)) => (idx, page),
-                    });
-
-                    BitPageVecIter::new(
-                        BitPageVecKind::SparseWithZeroesHole,
-                        Box::new(iter),
-                        min_last_bit_index(first.last_bit_index, second.last_bit_index),
-                    )
+                    BitPageVecIter::merge(BitPageVecKind::SparseWithZeroesHole, first, second, MergeOp::Or)
                 }
-                BitPageVecKind::AllOnes => second,
-                BitPageVecKind::SparseWithOnesHole => {
+                StreamKind::AllOnes => second,
+                StreamKind::OnesHole => {
                     // merge here... cross type
-                    let iter = first.iter.merge_join_by(second.iter, merge_cmp).filter_map(or_merge_cross_types);
-                    BitPageVecIter::new(
-                        BitPageVecKind::SparseWithOnesHole,
-                        Box::new(iter),
-                        min_last_bit_index(first.last_bit_index, second.last_bit_index),
-                    )
+                    BitPageVecIter::merge(BitPageVecKind::SparseWithOnesHole, first, second, MergeOp::OrCross)
                 }
             },
-            BitPageVecKind::AllOnes => first,
-            BitPageVecKind::SparseWithOnesHole => match second.kind {
-                BitPageVecKind::AllZeroes => first,
-                BitPageVecKind::SparseWithZeroesHole => {
+            StreamKind::AllOnes => first,
+            StreamKind::OnesHole => match second.kind {
+                StreamKind::AllZeroes => first,
+                StreamKind::ZeroesHole => {
                     // merge here... cross type
-                    let iter = second.iter.merge_join_by(first.iter, merge_cmp).filter_map(or_merge_cross_types);
-
                     // return type would be SparseWithOnesHole
-                    BitPageVecIter::new(
-                        BitPageVecKind::SparseWithOnesHole,
-                        Box::new(iter),
-                        min_last_bit_index(first.last_bit_index, second.last_bit_index),
-                    )
+                    BitPageVecIter::merge(BitPageVecKind::SparseWithOnesHole, second, first, MergeOp::OrCross)
                 }
-                BitPageVecKind::AllOnes => second,
-                BitPageVecKind::SparseWithOnesHole => {
+                StreamKind::AllOnes => second,
+                StreamKind::OnesHole => {
                     // merge here... same type with ones hole
                     // 1 | 1 => 1
                     // some | 1 => 1
                     // 1 | some => 1
                     // some | some => or(some)
                     // where 1 is hole
-                    let iter = first.iter.merge_join_by(second.iter, merge_cmp).filter_map(|either| match either {
-                        EitherOrBoth::Both((idx_1, mut page_one), (_idx_2, page_two)) => {
-                            page_one |= page_two;
-
-                            Some((idx_1, page_one))
-                        }
-                        EitherOrBoth::Left(_) | EitherOrBoth::Right(_) => None,
-                    });
-
-                    BitPageVecIter::new(
-                        BitPageVecKind::SparseWithOnesHole,
-                        Box::new(iter),
-                        min_last_bit_index(first.last_bit_index, second.last_bit_index),
-                    )
+                    BitPageVecIter::merge(BitPageVecKind::SparseWithOnesHole, first, second, MergeOp::OrOnesHoles)
                 }
             },
         };
@@ -193,79 +368,54 @@ impl<'a> BitPageVecIter<'a> {
     }
 
     pub fn and(first: BitPageVecIter<'a>, second: BitPageVecIter<'a>) -> BitPageVecIter<'a> {
+        BitPageVecIter::and_with(first, second, UniversePolicy::default())
+    }
+
+    /// same as and... different universes are combined as per policy
+    pub fn and_with(first: BitPageVecIter<'a>, second: BitPageVecIter<'a>, policy: UniversePolicy) -> BitPageVecIter<'a> {
+        let (first, second) = BitPageVecIter::align(first, second, policy);
+
         // if log_enabled!(target: "bit_page_vec_log", Level::Debug) {
         //     debug!(target: "bit_page_vec_log", "BitPageVecIter::AND first={:?} second={:?}", first, second);
         // }
 
         let result = match first.kind {
-            BitPageVecKind::AllZeroes => first, // essentially AllZeroes
-            BitPageVecKind::SparseWithZeroesHole => match second.kind {
-                BitPageVecKind::AllZeroes => second,
-                BitPageVecKind::SparseWithZeroesHole => {
-                    // merge here... same type (with zeroes hole)
-                    let iter = first.iter.merge_join_by(second.iter, merge_cmp).filter_map(|either| match either {
-                        EitherOrBoth::Both((idx_1, mut page_one), (_idx_2, page_two)) => {
-                            page_one &= page_two;
-
-                            if BitPage::is_zeroes(&page_one) {
-                                None
-                            } else {
-                                Some((idx_1, page_one))
-                            }
-                        }
-                        EitherOrBoth::Left(_) | EitherOrBoth::Right(_) => None,
-                    });
-
-                    BitPageVecIter::new(
-                        BitPageVecKind::SparseWithZeroesHole,
-                        Box::new(iter),
-                        min_last_bit_index(first.last_bit_index, second.last_bit_index),
-                    )
+            StreamKind::AllZeroes => first, // essentially AllZeroes
+            StreamKind::ZeroesHole => match second.kind {
+                StreamKind::AllZeroes => second,
+                StreamKind::ZeroesHole => {
+                    // leapfrog here... same type (with zeroes hole)
+                    let universe = min(first.universe, second.universe);
+                    let cursor = AndPageCursor::new(first.iter, second.iter);
+
+                    BitPageVecIter::with_cursor(BitPageVecKind::SparseWithZeroesHole, AnyPageCursor::And(Box::new(cursor)), universe)
                 }
-                BitPageVecKind::AllOnes => first,
-                BitPageVecKind::SparseWithOnesHole => {
-                    // merge here... cross type
-                    let iter = first.iter.merge_join_by(second.iter, merge_cmp).filter_map(and_merge_cross_types);
+                StreamKind::AllOnes => first,
+                StreamKind::OnesHole => {
+                    // seek here... cross type
+                    let universe = min(first.universe, second.universe);
+                    let cursor = AndOnesHolePageCursor::new(first.iter, second.iter);
 
                     // return type would be SparseWithZeroesHole
-                    BitPageVecIter::new(
-                        BitPageVecKind::SparseWithZeroesHole,
-                        Box::new(iter),
-                        min_last_bit_index(first.last_bit_index, second.last_bit_index),
-                    )
+                    BitPageVecIter::with_cursor(BitPageVecKind::SparseWithZeroesHole, AnyPageCursor::AndOnesHole(Box::new(cursor)), universe)
                 }
             },
-            BitPageVecKind::AllOnes => second,
-            BitPageVecKind::SparseWithOnesHole => match second.kind {
-                BitPageVecKind::AllZeroes => second, // essentially AllZeroes
-                BitPageVecKind::SparseWithZeroesHole => {
-                    // merge here... cross type
-                    // reverse the merge join... so first is always sparse with zeroes and second is always sparse with ones
-                    let iter = second.iter.merge_join_by(first.iter, merge_cmp).filter_map(and_merge_cross_types);
+            StreamKind::AllOnes => second,
+            StreamKind::OnesHole => match second.kind {
+                StreamKind::AllZeroes => second, // essentially AllZeroes
+                StreamKind::ZeroesHole => {
+                    // seek here... cross type
+                    // swap the sides... so zeroes hole one drives and ones hole one is seeked
+                    let universe = min(first.universe, second.universe);
+                    let cursor = AndOnesHolePageCursor::new(second.iter, first.iter);
 
                     // return type would be SparseWithZeroesHole
-                    BitPageVecIter::new(
-                        BitPageVecKind::SparseWithZeroesHole,
-                        Box::new(iter),
-                        min_last_bit_index(first.last_bit_index, second.last_bit_index),
-                    )
+                    BitPageVecIter::with_cursor(BitPageVecKind::SparseWithZeroesHole, AnyPageCursor::AndOnesHole(Box::new(cursor)), universe)
                 }
-                BitPageVecKind::AllOnes => first,
-                BitPageVecKind::SparseWithOnesHole => {
+                StreamKind::AllOnes => first,
+                StreamKind::OnesHole => {
                     // merge here... same type (with ones hole)
-                    let iter = first.iter.merge_join_by(second.iter, merge_cmp).map(|either| match either {
-                        EitherOrBoth::Both((idx_1, mut page_one), (_idx_2, page_two)) => {
-                            page_one &= page_two;
-
-                            (idx_1, page_one)
-                        }
-                        EitherOrBoth::Left((idx, page)) | EitherOrBoth::Right((idx, page)) => (idx, page),
-                    });
-                    BitPageVecIter::new(
-                        BitPageVecKind::SparseWithOnesHole,
-                        Box::new(iter),
-                        min_last_bit_index(first.last_bit_index, second.last_bit_index),
-                    )
+                    BitPageVecIter::merge(BitPageVecKind::SparseWithOnesHole, first, second, MergeOp::AndOnesHoles)
                 }
             },
         };
@@ -277,214 +427,234 @@ impl<'a> BitPageVecIter<'a> {
         result
     }
 
-    pub(crate) fn compact_sparse_with_zeroes_hole(pages: Vec<BitPageWithPosition>, last_bit_index: (usize, usize)) -> BitPageVec {
-        // if log_enabled!(target: "bit_page_vec_log", Level::Trace) {
-        //     trace!(target: "bit_page_vec_log", "compact_sparse_with_zeroes_hole - pages len={}", pages.len());
-        // }
-
-        let result = if pages.is_empty() {
-            BitPageVec::all_zeros(last_bit_index)
-        } else if pages.len() <= 10_000 {
-            BitPageVec::new(BitPageVecKind::SparseWithZeroesHole, Some(pages), last_bit_index)
-        } else {
-            let start_page = pages[0].page_idx;
-            let end_page = pages[pages.len() - 1].page_idx;
-            let max_possible_length = (end_page - start_page + 1) as f64;
-            let actual_length = pages.len() as f64;
-
-            // if log_enabled!(target: "bit_page_vec_log", Level::Trace) {
-            //     trace!(target: "bit_page_vec_log", "compact_sparse_with_zeroes_hole - start_page={} end_page={} max_possible_length={} actual_length={}", start_page, end_page, max_possible_length, actual_length);
-            // }
-
-            // find start page, end page, and length
-            // if length >= 75% of (end - start) page
-            // and # of active bits >= 75% of active bits needed for fully packed 75%
-            if actual_length >= 0.75 * max_possible_length
-                && BitPageVec::count_ones(Some(&pages)) as f64 >= 0.75 * max_possible_length * 64.0
-            {
-                // if log_enabled!(target: "bit_page_vec_log", Level::Trace) {
-                //     trace!(target: "bit_page_vec_log", "compact_sparse_with_zeroes_hole::compacting - ones={}", BitPageVec::count_ones(Some(&pages)));
-                // }
-
-                // filter out all page with max value
-                // and include pages with holes
-                let pages = (0..=last_bit_index.0)
-                    .merge_join_by(pages.into_iter(), |page_1_idx, BitPageWithPosition { page_idx: page_2_idx, .. }| {
-                        page_1_idx.cmp(page_2_idx)
-                    })
-                    .filter_map(|either| {
-                        match either {
-                            EitherOrBoth::Both(_, BitPageWithPosition { page_idx, bit_page }) => {
-                                if BitPage::is_ones(&bit_page) {
-                                    None
-                                } else {
-                                    Some(BitPageWithPosition { page_idx, bit_page })
-                                }
-                            }
-                            EitherOrBoth::Left(page_idx) => Some(BitPageWithPosition {
-                                page_idx,
-                                bit_page: BitPage::zeroes(),
-                            }),
-                            EitherOrBoth::Right(_) => {
-                                // this case should not arise
-                                None
-                            }
-                        }
-                    })
-                    .collect_vec();
+    pub fn xor(first: BitPageVecIter<'a>, second: BitPageVecIter<'a>) -> BitPageVecIter<'a> {
+        BitPageVecIter::xor_with(first, second, UniversePolicy::default())
+    }
 
-                BitPageVec::new(BitPageVecKind::SparseWithOnesHole, Some(pages), last_bit_index)
-            } else {
-                BitPageVec::new(BitPageVecKind::SparseWithZeroesHole, Some(pages), last_bit_index)
-            }
-        };
+    /// same as xor... different universes are combined as per policy
+    pub fn xor_with(first: BitPageVecIter<'a>, second: BitPageVecIter<'a>, policy: UniversePolicy) -> BitPageVecIter<'a> {
+        let (first, second) = BitPageVecIter::align(first, second, policy);
+
+        match first.kind {
+            StreamKind::AllZeroes => second,
+            StreamKind::AllOnes => second.not(),
+            StreamKind::ZeroesHole => match second.kind {
+                StreamKind::AllZeroes => first,
+                StreamKind::AllOnes => first.not(),
+                StreamKind::ZeroesHole => {
+                    // merge here... same type with zeroes hole
+                    // 0 ^ 0 => 0
+                    // some ^ 0 => some
+                    // 0 ^ some => some
+                    // some ^ some => xor(some)
+                    BitPageVecIter::merge(BitPageVecKind::SparseWithZeroesHole, first, second, MergeOp::Xor)
+                }
+                StreamKind::OnesHole => {
+                    // merge here... cross type
+                    // return type would be SparseWithOnesHole
+                    BitPageVecIter::merge(BitPageVecKind::SparseWithOnesHole, first, second, MergeOp::XorCross)
+                }
+            },
+            StreamKind::OnesHole => match second.kind {
+                StreamKind::AllZeroes => first,
+                StreamKind::AllOnes => first.not(),
+                StreamKind::ZeroesHole => {
+                    // merge here... cross type
+                    // reverse the merge join... so first is always sparse with zeroes and second is always sparse with ones
+                    // return type would be SparseWithOnesHole
+                    BitPageVecIter::merge(BitPageVecKind::SparseWithOnesHole, second, first, MergeOp::XorCross)
+                }
+                StreamKind::OnesHole => {
+                    // merge here... same type with ones hole
+                    // 1 ^ 1 => 0
+                    // some ^ 1 => !some
+                    // 1 ^ some => !some
+                    // some ^ some => xor(some)
+                    // where 1 is hole... return type would be sparse with zeroes
+                    BitPageVecIter::merge(BitPageVecKind::SparseWithZeroesHole, first, second, MergeOp::XorOnesHoles)
+                }
+            },
+        }
+    }
 
-        // if log_enabled!(target: "bit_page_vec_log", Level::Trace) {
-        //     trace!(target: "bit_page_vec_log", "compact_sparse_with_zeroes_hole::result={:?}", result);
-        // }
+    // active bits of first which are not active in second... i.e. first & !second without flipping second
+    pub fn and_not(first: BitPageVecIter<'a>, second: BitPageVecIter<'a>) -> BitPageVecIter<'a> {
+        BitPageVecIter::and_not_with(first, second, UniversePolicy::default())
+    }
 
-        result
+    /// same as and_not... different universes are combined as per policy
+    pub fn and_not_with(first: BitPageVecIter<'a>, second: BitPageVecIter<'a>, policy: UniversePolicy) -> BitPageVecIter<'a> {
+        let (first, second) = BitPageVecIter::align(first, second, policy);
+
+        match first.kind {
+            StreamKind::AllZeroes => first, // essentially AllZeroes
+            StreamKind::AllOnes => second.not(),
+            StreamKind::ZeroesHole => match second.kind {
+                StreamKind::AllZeroes => first,
+                StreamKind::AllOnes => BitPageVec::all_zeros(min(first.universe, second.universe)).into_page_iter(),
+                StreamKind::ZeroesHole => {
+                    // merge here... same type with zeroes hole
+                    // some & !0 => some
+                    // 0 & !some => 0
+                    // some & !some => and_not(some)
+                    BitPageVecIter::merge(BitPageVecKind::SparseWithZeroesHole, first, second, MergeOp::AndNot)
+                }
+                StreamKind::OnesHole => {
+                    // merge here... cross type
+                    // some & !1 => 0
+                    // 0 & !some => 0
+                    // some & !some => and_not(some)
+                    // return type would be SparseWithZeroesHole
+                    BitPageVecIter::merge(BitPageVecKind::SparseWithZeroesHole, first, second, MergeOp::AndNotOnesHole)
+                }
+            },
+            StreamKind::OnesHole => match second.kind {
+                StreamKind::AllZeroes => first,
+                StreamKind::AllOnes => BitPageVec::all_zeros(min(first.universe, second.universe)).into_page_iter(),
+                StreamKind::ZeroesHole => {
+                    // merge here... cross type
+                    // some & !0 => some
+                    // 1 & !some => !some
+                    // some & !some => and_not(some)
+                    // return type would be SparseWithOnesHole
+                    BitPageVecIter::merge(BitPageVecKind::SparseWithOnesHole, first, second, MergeOp::OnesHoleAndNot)
+                }
+                StreamKind::OnesHole => {
+                    // merge here... same type with ones hole
+                    // 1 & !1 => 0
+                    // some & !1 => 0
+                    // 1 & !some => !some
+                    // some & !some => and_not(some)
+                    // where 1 is hole... return type would be sparse with zeroes
+                    BitPageVecIter::merge(BitPageVecKind::SparseWithZeroesHole, first, second, MergeOp::AndNotOnesHoles)
+                }
+            },
+        }
     }
 
-    pub(crate) fn compact_sparse_with_ones_hole(pages: Vec<BitPageWithPosition>, last_bit_index: (usize, usize)) -> BitPageVec {
-        // if log_enabled!(target: "bit_page_vec_log", Level::Trace) {
-        //     trace!(target: "bit_page_vec_log", "compact_sparse_with_ones_hole - pages len={}", pages.len());
-        // }
+    // merge join of both streams into a stream of kind
+    fn merge(kind: BitPageVecKind, first: BitPageVecIter<'a>, second: BitPageVecIter<'a>, op: MergeOp) -> BitPageVecIter<'a> {
+        let universe = min(first.universe, second.universe);
+        let cursor = MergePageCursor::new(first.iter, second.iter, op);
 
-        let result = if pages.is_empty() {
-            BitPageVec::all_ones(last_bit_index)
-        } else if pages.len() <= 10_000 {
-            BitPageVec::new(BitPageVecKind::SparseWithOnesHole, Some(pages), last_bit_index)
-        } else {
-            let start_page = pages[0].page_idx;
-            let end_page = pages[pages.len() - 1].page_idx;
-            let max_possible_length = (end_page - start_page + 1) as f64;
-            let actual_length = pages.len() as f64;
-
-            // if log_enabled!(target: "bit_page_vec_log", Level::Trace) {
-            //     debug!(target: "bit_page_vec_log", "compact_sparse_with_ones_hole - start_page={} end_page={} max_possible_length={} actual_length={}", start_page, end_page, max_possible_length, actual_length);
-            // }
-
-            // find start page, end page, and length
-            // if length >= 75% of (end - start) page
-            // and # of active bits <= 25% of active bits needed for fully packed 75%
-            if actual_length >= 0.75 * max_possible_length
-                && BitPageVec::count_ones(Some(&pages)) as f64 <= 0.25 * max_possible_length * 64.0
-            {
-                // if log_enabled!(target: "bit_page_vec_log", Level::Trace) {
-                //     debug!(target: "bit_page_vec_log", "compact_sparse_with_ones_hole::compacting - ones={}", BitPageVec::count_ones(Some(&pages)));
-                // }
-
-                // filter out all page with max value
-                // and include pages with holes
-                let pages = (0..=last_bit_index.0)
-                    .merge_join_by(pages.into_iter(), |page_1_idx, BitPageWithPosition { page_idx: page_2_idx, .. }| {
-                        page_1_idx.cmp(page_2_idx)
-                    })
-                    .filter_map(|either| {
-                        match either {
-                            EitherOrBoth::Both(_, BitPageWithPosition { page_idx, bit_page }) => {
-                                if BitPage::is_zeroes(&bit_page) {
-                                    None
-                                } else {
-                                    Some(BitPageWithPosition { page_idx, bit_page })
-                                }
-                            }
-                            EitherOrBoth::Left(page_idx) => Some(BitPageWithPosition {
-                                page_idx,
-                                bit_page: BitPage::ones(),
-                            }),
-                            EitherOrBoth::Right(_) => {
-                                // this case should not arise
-                                None
-                            }
-                        }
-                    })
-                    .collect_vec();
+        BitPageVecIter::with_cursor(kind, AnyPageCursor::Merge(Box::new(cursor)), universe)
+    }
 
-                BitPageVec::new(BitPageVecKind::SparseWithZeroesHole, Some(pages), last_bit_index)
+    pub(crate) fn compact_sparse_with_zeroes_hole(pages: Vec<BitPageWithPosition>, universe: Universe) -> BitPageVec {
+        Self::compact_pages(pages, BitPage::zeroes(), universe, &DEFAULT_COMPACTION_POLICY)
+    }
+
+    // stores sparse pages (whose missing pages have `hole` value) the way policy decides
+    pub(crate) fn compact_pages(
+        pages: Vec<BitPageWithPosition>,
+        hole: u64,
+        universe: Universe,
+        policy: &dyn CompactionPolicy,
+    ) -> BitPageVec {
+        let zeroes_hole = BitPage::is_zeroes(&hole);
+        let stats = CompactionStats {
+            kind: if zeroes_hole {
+                BitPageVecKind::SparseWithZeroesHole
             } else {
-                BitPageVec::new(BitPageVecKind::SparseWithOnesHole, Some(pages), last_bit_index)
-            }
+                BitPageVecKind::SparseWithOnesHole
+            },
+            universe,
+            pages: pages.len(),
+            span: match (pages.first(), pages.last()) {
+                (Some(first), Some(last)) => last.page_idx - first.page_idx + 1,
+                _ => 0,
+            },
+            ones: pages.iter().map(|page| page.bit_page.count_ones() as u64).sum(),
+            runs: if zeroes_hole { count_runs(&pages) } else { 0 },
         };
 
+        if pages.is_empty() {
+            let result = if zeroes_hole {
+                BitPageVec::all_zeros(universe)
+            } else {
+                BitPageVec::all_ones(universe)
+            };
+
+            policy.observe(&stats, result.kind);
+            return result;
+        }
+
+        let target = policy.target(&stats);
+        policy.observe(&stats, target);
+
         // if log_enabled!(target: "bit_page_vec_log", Level::Trace) {
-        //     trace!(target: "bit_page_vec_log", "compact_sparse_with_ones_hole::result={:?}", result);
+        //     trace!(target: "bit_page_vec_log", "compact_pages - stats={:?} target={:?}", stats, target);
         // }
 
-        result
-    }
-}
-
-impl BitPageVec {
-    pub fn iter(&self) -> BitPageVecIter {
-        match self.kind {
-            BitPageVecKind::AllZeroes => {
-                let iter = empty::<PageItem>();
-                BitPageVecIter::new(BitPageVecKind::AllZeroes, Box::new(iter), self.last_bit_index)
+        // runs and containers are built out of zeroes hole pages
+        let zeroes_hole_pages = |pages| {
+            if zeroes_hole {
+                pages
+            } else {
+                swap_hole_pages(pages, universe, BitPage::ones())
             }
-            BitPageVecKind::SparseWithZeroesHole => {
-                if let Some(ref pages) = self.pages {
-                    let iter = pages
-                        .iter()
-                        .map(|BitPageWithPosition { page_idx, bit_page }| (*page_idx, *bit_page));
-                    BitPageVecIter::new(BitPageVecKind::SparseWithZeroesHole, Box::new(iter), self.last_bit_index)
-                } else {
-                    let iter = empty::<PageItem>();
-                    BitPageVecIter::new(BitPageVecKind::AllZeroes, Box::new(iter), self.last_bit_index)
-                }
+        };
+
+        match (target, zeroes_hole) {
+            (BitPageVecKind::AllZeroes | BitPageVecKind::AllOnes, _) => BitPageVec::new(stats.kind, Some(pages), universe),
+            (BitPageVecKind::SparseWithZeroesHole, true) | (BitPageVecKind::SparseWithOnesHole, false) => {
+                BitPageVec::new(target, Some(pages), universe)
             }
-            BitPageVecKind::AllOnes => {
-                let iter = empty::<PageItem>();
-                BitPageVecIter::new(BitPageVecKind::AllOnes, Box::new(iter), self.last_bit_index)
+            (BitPageVecKind::SparseWithZeroesHole, false) => {
+                // filter out all page with zero value and include pages with holes
+                BitPageVec::new(target, Some(swap_hole_pages(pages, universe, BitPage::ones())), universe)
             }
-            BitPageVecKind::SparseWithOnesHole => {
-                if let Some(ref pages) = self.pages {
-                    let iter = pages
-                        .iter()
-                        .map(|BitPageWithPosition { page_idx, bit_page }| (*page_idx, *bit_page));
-                    BitPageVecIter::new(BitPageVecKind::SparseWithOnesHole, Box::new(iter), self.last_bit_index)
-                } else {
-                    let iter = empty::<PageItem>();
-                    BitPageVecIter::new(BitPageVecKind::AllOnes, Box::new(iter), self.last_bit_index)
-                }
+            (BitPageVecKind::SparseWithOnesHole, true) => {
+                // filter out all page with max value and include pages with holes
+                BitPageVec::new(target, Some(swap_hole_pages(pages, universe, BitPage::zeroes())), universe)
             }
+            (BitPageVecKind::Runs, _) => BitPageVec::from_runs(pages_to_runs(&zeroes_hole_pages(pages)), universe),
+            (BitPageVecKind::Containers, _) => BitPageVec::from_containers(pages_to_containers(zeroes_hole_pages(pages)), universe),
         }
     }
+}
 
-    pub fn into_iter<'a>(self) -> BitPageVecIter<'a> {
-        match self.kind {
-            BitPageVecKind::AllZeroes => {
-                let iter = empty::<PageItem>();
-                BitPageVecIter::new(BitPageVecKind::AllZeroes, Box::new(iter), self.last_bit_index)
-            }
-            BitPageVecKind::SparseWithZeroesHole => {
-                if let Some(pages) = self.pages {
-                    let iter = pages
-                        .into_iter()
-                        .map(|BitPageWithPosition { page_idx, bit_page }| (page_idx, bit_page));
-                    BitPageVecIter::new(BitPageVecKind::SparseWithZeroesHole, Box::new(iter), self.last_bit_index)
-                } else {
-                    let iter = empty::<PageItem>();
-                    BitPageVecIter::new(BitPageVecKind::AllZeroes, Box::new(iter), self.last_bit_index)
-                }
-            }
-            BitPageVecKind::AllOnes => {
-                let iter = empty::<PageItem>();
-                BitPageVecIter::new(BitPageVecKind::AllOnes, Box::new(iter), self.last_bit_index)
+impl BitPageVec {
+    /// stream of the pages (this used to be iter()... which now yields ids)
+    pub fn page_iter(&self) -> BitPageVecIter<'_> {
+        let (kind, cursor) = match (self.kind, &self.pages, &self.runs, &self.containers) {
+            (BitPageVecKind::Containers, _, _, Some(containers)) => (
+                BitPageVecKind::SparseWithZeroesHole,
+                AnyPageCursor::Containers(ContainersPageCursor::new(containers.as_slice())),
+            ),
+            (BitPageVecKind::Runs, _, Some(runs), _) => (
+                BitPageVecKind::SparseWithZeroesHole,
+                AnyPageCursor::Runs(RunsPageCursor::new(runs.as_slice())),
+            ),
+            (BitPageVecKind::SparseWithZeroesHole | BitPageVecKind::SparseWithOnesHole, Some(pages), _, _) => {
+                (self.kind, AnyPageCursor::Slice(SlicePageCursor::new(pages.as_slice())))
             }
-            BitPageVecKind::SparseWithOnesHole => {
-                if let Some(pages) = self.pages {
-                    let iter = pages
-                        .into_iter()
-                        .map(|BitPageWithPosition { page_idx, bit_page }| (page_idx, bit_page));
-                    BitPageVecIter::new(BitPageVecKind::SparseWithOnesHole, Box::new(iter), self.last_bit_index)
-                } else {
-                    let iter = empty::<PageItem>();
-                    BitPageVecIter::new(BitPageVecKind::AllOnes, Box::new(iter), self.last_bit_index)
-                }
+            (BitPageVecKind::AllOnes | BitPageVecKind::SparseWithOnesHole, _, _, _) => (BitPageVecKind::AllOnes, AnyPageCursor::Empty),
+            _ => (BitPageVecKind::AllZeroes, AnyPageCursor::Empty),
+        };
+
+        BitPageVecIter::with_cursor(kind, cursor, self.last_bit_index)
+    }
+
+    /// owned stream of the pages (this used to be into_iter()... which now yields ids)
+    pub fn into_page_iter<'a>(self) -> BitPageVecIter<'a> {
+        let (kind, cursor) = match (self.kind, self.pages, self.runs, self.containers) {
+            (BitPageVecKind::Containers, _, _, Some(containers)) => (
+                BitPageVecKind::SparseWithZeroesHole,
+                AnyPageCursor::OwnedContainers(ContainersPageCursor::new(containers)),
+            ),
+            (BitPageVecKind::Runs, _, Some(runs), _) => (
+                BitPageVecKind::SparseWithZeroesHole,
+                AnyPageCursor::OwnedRuns(RunsPageCursor::new(runs)),
+            ),
+            (BitPageVecKind::SparseWithZeroesHole | BitPageVecKind::SparseWithOnesHole, Some(pages), _, _) => {
+                (self.kind, AnyPageCursor::OwnedSlice(SlicePageCursor::new(pages)))
             }
-        }
+            (BitPageVecKind::AllOnes | BitPageVecKind::SparseWithOnesHole, _, _, _) => (BitPageVecKind::AllOnes, AnyPageCursor::Empty),
+            _ => (BitPageVecKind::AllZeroes, AnyPageCursor::Empty),
+        };
+
+        BitPageVecIter::with_cursor(kind, cursor, self.last_bit_index)
     }
 }
 
@@ -518,26 +688,105 @@ pub(crate) fn or_merge_cross_types(either: EitherOrBoth<PageItem, PageItem>) ->
 #[inline]
 // first one is sparse with zeroes, second one is sparse with ones
 // i.e. first hole = 0 and second hole = 1
-// * MISSING: first hole & second hole => 0... return type would be sparse with zeroes
-// * RIGHT: first hole & some => 0... should be filtered as return type would be sparse with zeroes
-// * LEFT: some & second hole => some
-// * BOTH: some & some => some
-pub(crate) fn and_merge_cross_types(either: EitherOrBoth<PageItem, PageItem>) -> Option<PageItem> {
+// MISSING: first hole(0) ^ second hole(1) => 1... return type would be sparse with ones
+// RIGHT: first hole(0) ^ some => some
+// LEFT: some ^ second hole(1) => !some
+// BOTH: some ^ some => some
+pub(crate) fn xor_merge_cross_types(either: EitherOrBoth<PageItem, PageItem>) -> PageItem {
     match either {
-        EitherOrBoth::Both((idx_1, mut page_one), (_idx_2, page_two)) => {
-            page_one &= page_two;
+        EitherOrBoth::Both((idx_1, page_one), (_idx_2, page_two)) => (idx_1, page_one ^ page_two),
+        EitherOrBoth::Left((idx, page)) => (idx, !page),
+        EitherOrBoth::Right(page) => page,
+    }
+}
 
-            // some & some
-            Some((idx_1, page_one))
-        }
-        EitherOrBoth::Left((idx, page)) => {
-            // some & 1
-            Some((idx, page))
-        }
-        EitherOrBoth::Right(_) => None, // 0 & some
+#[inline]
+pub(crate) fn non_zero_page((idx, page): PageItem) -> Option<PageItem> {
+    if BitPage::is_zeroes(&page) {
+        None
+    } else {
+        Some((idx, page))
     }
 }
 
-pub(crate) fn min_last_bit_index(first: (usize, usize), second: (usize, usize)) -> (usize, usize) {
-    min(first, second)
+// re-express pages of a sparse vector with `hole` valued holes as pages of a sparse vector with the inverted hole...
+// i.e. pages equal to the new hole are dropped and missing pages are materialized with the old hole value
+pub(crate) fn swap_hole_pages(pages: Vec<BitPageWithPosition>, universe: Universe, hole: u64) -> Vec<BitPageWithPosition> {
+    let new_hole = !hole;
+
+    (0..universe.pages())
+        .merge_join_by(pages, |page_1_idx, BitPageWithPosition { page_idx: page_2_idx, .. }| {
+            page_1_idx.cmp(page_2_idx)
+        })
+        .filter_map(|either| {
+            match either {
+                EitherOrBoth::Both(_, BitPageWithPosition { page_idx, bit_page }) => {
+                    if bit_page == new_hole {
+                        None
+                    } else {
+                        Some(BitPageWithPosition { page_idx, bit_page })
+                    }
+                }
+                EitherOrBoth::Left(page_idx) => Some(BitPageWithPosition { page_idx, bit_page: hole }),
+                EitherOrBoth::Right(_) => {
+                    // this case should not arise
+                    None
+                }
+            }
+        })
+        .collect_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::bit_page_vec::BitPageVecKind;
+    use crate::bit_page_vec_iter::BitPageVecIter;
+    use crate::{BitPageVec, PageCursor};
+
+    #[test]
+    fn test_runs_and_containers_streams_are_zeroes_hole() {
+        let pages = vec![(0, 0b1010), (3, u64::MAX)];
+        let expected = vec![1, 3].into_iter().chain(192..256).collect_vec();
+
+        for kind in [BitPageVecKind::Runs, BitPageVecKind::Containers] {
+            let iter = BitPageVecIter::new(kind, Box::new(pages.clone().into_iter()), (4, 0));
+            assert_eq!(iter.kind(), &BitPageVecKind::SparseWithZeroesHole);
+
+            let bit_page_vec = iter.into_bit_page_vec();
+            assert_eq!(bit_page_vec.iter().collect_vec(), expected);
+
+            let not = BitPageVecIter::new(kind, Box::new(pages.clone().into_iter()), (4, 0)).not();
+            assert_eq!(not.kind(), &BitPageVecKind::SparseWithOnesHole);
+            assert_eq!(not.into_bit_page_vec().active_bits_count(), 256 - expected.len());
+
+            let all_ones = BitPageVec::all_ones((4, 0));
+            let and = BitPageVecIter::and(
+                BitPageVecIter::new(kind, Box::new(pages.clone().into_iter()), (4, 0)),
+                all_ones.page_iter(),
+            );
+            assert_eq!(and.into_bit_page_vec().iter().collect_vec(), expected);
+        }
+    }
+
+    #[test]
+    fn test_with_universe_extends_ones_hole() {
+        // hole(1) of (2, 10) covers ids 0..138... stored bits past it (and pages past it) must not leak into the extension
+        let pages = vec![(0, !0b100), (2, u64::MAX), (3, 1)];
+        let expected = (0..138).filter(|id| *id != 2).collect_vec();
+
+        let iter = BitPageVecIter::new(BitPageVecKind::SparseWithOnesHole, Box::new(pages.clone().into_iter()), (2, 10));
+        let extended = iter.with_universe((5, 0));
+        assert_eq!(extended.kind(), &BitPageVecKind::SparseWithOnesHole);
+        assert_eq!(extended.into_bit_page_vec().iter().collect_vec(), expected);
+
+        let iter = BitPageVecIter::new(BitPageVecKind::SparseWithOnesHole, Box::new(pages.into_iter()), (2, 10));
+        let mut cursor = iter.with_universe((5, 0)).into_cursor();
+        cursor.advance_to(1);
+        assert_eq!(cursor.collect_vec(), vec![(2, 0b11_1111_1111), (3, 0), (4, 0)]);
+
+        let all_ones = BitPageVec::all_ones((1, 0)).into_page_iter().with_universe((3, 0));
+        assert_eq!(all_ones.into_bit_page_vec().iter().collect_vec(), (0..64).collect_vec());
+    }
 }
@@ -0,0 +1,330 @@
+use std::cmp::{max, min};
+use std::iter::empty;
+
+use itertools::Itertools;
+
+// @author shailendra.sharma
+use crate::bit_page::{join_bit_index, split_bit_index, BitPageWithPosition};
+use crate::bit_page_vec::BitPageVecKind;
+use crate::bit_page_vec_containers::CONTAINER_PAGES;
+use crate::bit_page_vec_iter::{PageItem, PageIterator};
+use crate::bit_page_vec_runs::BitRun;
+use crate::{BitPage, BitPageVec};
+
+impl BitPageVec {
+    /// smallest active id
+    pub fn min(&self) -> Option<u64> {
+        self.next_set_bit(0)
+    }
+
+    /// largest active id
+    pub fn max(&self) -> Option<u64> {
+        self.prev_set_bit(u64::MAX)
+    }
+
+    /// smallest active id >= from
+    pub fn next_set_bit(&self, from: u64) -> Option<u64> {
+        self.next_bit(from, true)
+    }
+
+    /// largest active id <= from
+    pub fn prev_set_bit(&self, from: u64) -> Option<u64> {
+        self.prev_bit(from, true)
+    }
+
+    /// smallest inactive id >= from (within the universe)
+    pub fn next_clear_bit(&self, from: u64) -> Option<u64> {
+        self.next_bit(from, false)
+    }
+
+    /// largest inactive id <= from (within the universe)
+    pub fn prev_clear_bit(&self, from: u64) -> Option<u64> {
+        self.prev_bit(from, false)
+    }
+
+    fn next_bit(&self, from: u64, set: bool) -> Option<u64> {
+        let universe_len = self.universe_len();
+        if from >= universe_len {
+            return None;
+        }
+
+        let next = match self.kind {
+            BitPageVecKind::Runs => next_in_runs(self.runs.as_deref().unwrap_or(&[]), from, set),
+            _ => {
+                let (from_page, _) = split_bit_index(from);
+                next_in_pages(self.pages_from(from_page), self.hole(), from, set)
+            }
+        };
+
+        next.filter(|index| *index < universe_len)
+    }
+
+    fn prev_bit(&self, from: u64, set: bool) -> Option<u64> {
+        let universe_len = self.universe_len();
+        if universe_len == 0 {
+            return None;
+        }
+
+        // ids past the universe are never active (nor inactive)
+        let from = min(from, universe_len - 1);
+
+        match self.kind {
+            BitPageVecKind::Runs => prev_in_runs(self.runs.as_deref().unwrap_or(&[]), from, set),
+            _ => {
+                let (from_page, _) = split_bit_index(from);
+                prev_in_pages(self.pages_upto(from_page), self.hole(), from, set)
+            }
+        }
+    }
+
+    // value of the pages which are not stored
+    fn hole(&self) -> u64 {
+        match self.kind {
+            BitPageVecKind::AllOnes | BitPageVecKind::SparseWithOnesHole => BitPage::ones(),
+            _ => BitPage::zeroes(),
+        }
+    }
+
+    // stored pages from page_idx onwards in ascending order
+    fn pages_from(&self, page_idx: usize) -> PageIterator<'_> {
+        match (self.kind, self.pages.as_deref(), self.containers.as_deref()) {
+            (BitPageVecKind::SparseWithZeroesHole | BitPageVecKind::SparseWithOnesHole, Some(pages), _) => {
+                let position = pages.partition_point(|page| page.page_idx < page_idx);
+                Box::new(pages[position..].iter().map(page_item))
+            }
+            (BitPageVecKind::Containers, _, Some(containers)) => {
+                let position = containers.partition_point(|container| container.region_idx() < page_idx / CONTAINER_PAGES);
+                Box::new(
+                    containers[position..]
+                        .iter()
+                        .flat_map(|container| container.page_items())
+                        .skip_while(move |(idx, _)| *idx < page_idx),
+                )
+            }
+            _ => Box::new(empty::<PageItem>()),
+        }
+    }
+
+    // stored pages upto (and including) page_idx in descending order
+    fn pages_upto(&self, page_idx: usize) -> PageIterator<'_> {
+        match (self.kind, self.pages.as_deref(), self.containers.as_deref()) {
+            (BitPageVecKind::SparseWithZeroesHole | BitPageVecKind::SparseWithOnesHole, Some(pages), _) => {
+                let position = pages.partition_point(|page| page.page_idx <= page_idx);
+                Box::new(pages[..position].iter().rev().map(page_item))
+            }
+            (BitPageVecKind::Containers, _, Some(containers)) => {
+                let position = containers.partition_point(|container| container.region_idx() <= page_idx / CONTAINER_PAGES);
+                Box::new(
+                    containers[..position]
+                        .iter()
+                        .rev()
+                        .flat_map(|container| container.page_items().collect_vec().into_iter().rev())
+                        .skip_while(move |(idx, _)| *idx > page_idx),
+                )
+            }
+            _ => Box::new(empty::<PageItem>()),
+        }
+    }
+}
+
+#[inline]
+fn page_item(page: &BitPageWithPosition) -> PageItem {
+    (page.page_idx, page.bit_page)
+}
+
+// first id >= from with the wanted value... pages are the stored pages from the page of from in ascending order
+// and hole is the value of the missing ones
+pub(crate) fn next_in_pages<I>(pages: I, hole: u64, from: u64, set: bool) -> Option<u64>
+where
+    I: Iterator<Item = PageItem>,
+{
+    let (from_page, from_bit) = split_bit_index(from);
+    let wanted = |bit_page: u64| if set { bit_page } else { !bit_page };
+    let hole = wanted(hole);
+
+    // first id of page_idx (or from itself within its own page)
+    let page_start = |page_idx: usize| max(join_bit_index(page_idx, 0), from);
+
+    let mut expected = from_page;
+    for (page_idx, bit_page) in pages {
+        if page_idx > expected && hole != 0 {
+            return Some(page_start(expected));
+        }
+
+        let mut bit_page = wanted(bit_page);
+        if page_idx == from_page {
+            bit_page &= BitPage::ones() << from_bit;
+        }
+
+        if bit_page != 0 {
+            return Some(join_bit_index(page_idx, bit_page.trailing_zeros() as usize));
+        }
+
+        expected = page_idx + 1;
+    }
+
+    if hole != 0 {
+        Some(page_start(expected))
+    } else {
+        None
+    }
+}
+
+// last id <= from with the wanted value... pages are the stored pages upto the page of from in descending order
+// and hole is the value of the missing ones
+pub(crate) fn prev_in_pages<I>(pages: I, hole: u64, from: u64, set: bool) -> Option<u64>
+where
+    I: Iterator<Item = PageItem>,
+{
+    let (from_page, from_bit) = split_bit_index(from);
+    let wanted = |bit_page: u64| if set { bit_page } else { !bit_page };
+    let hole = wanted(hole);
+
+    // last id of page_idx (or from itself within its own page)
+    let page_end = |page_idx: usize| min(join_bit_index(page_idx, BitPage::MAX_BITS - 1), from);
+
+    let mut expected = Some(from_page);
+    for (page_idx, bit_page) in pages {
+        match expected {
+            Some(expected) if expected > page_idx && hole != 0 => return Some(page_end(expected)),
+            _ => {}
+        }
+
+        let mut bit_page = wanted(bit_page);
+        if page_idx == from_page {
+            bit_page &= BitPage::ones() >> (BitPage::MAX_BITS - 1 - from_bit);
+        }
+
+        if bit_page != 0 {
+            let bit_idx = BitPage::MAX_BITS - 1 - bit_page.leading_zeros() as usize;
+            return Some(join_bit_index(page_idx, bit_idx));
+        }
+
+        expected = page_idx.checked_sub(1);
+    }
+
+    match expected {
+        Some(expected) if hole != 0 => Some(page_end(expected)),
+        _ => None,
+    }
+}
+
+fn next_in_runs(runs: &[BitRun], from: u64, set: bool) -> Option<u64> {
+    let mut position = runs.partition_point(|run| run.end() <= from);
+
+    if set {
+        runs.get(position).map(|run| max(run.start(), from))
+    } else {
+        // step over runs covering the candidate
+        let mut candidate = from;
+        while let Some(run) = runs.get(position).filter(|run| run.start() <= candidate) {
+            candidate = run.end();
+            position += 1;
+        }
+
+        Some(candidate)
+    }
+}
+
+fn prev_in_runs(runs: &[BitRun], from: u64, set: bool) -> Option<u64> {
+    let mut position = runs.partition_point(|run| run.start() <= from);
+
+    if set {
+        position.checked_sub(1).map(|position| min(runs[position].end() - 1, from))
+    } else {
+        // step back over runs covering the candidate
+        let mut candidate = from;
+        while position > 0 && runs[position - 1].end() > candidate {
+            candidate = runs[position - 1].start().checked_sub(1)?;
+            position -= 1;
+        }
+
+        Some(candidate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bit_page_vec_containers::pages_to_containers;
+    use crate::bit_page_vec_fixtures::vectors;
+    use crate::bit_page_vec_runs::BitRun;
+    use crate::BitPageVec;
+
+    // naive navigation over is_bit_set
+    fn naive(bit_page_vec: &BitPageVec, from: u64, set: bool, forward: bool) -> Option<u64> {
+        let universe_len = bit_page_vec.universe().len();
+        let matches = |index: &u64| bit_page_vec.contains(*index) == set;
+
+        if forward {
+            (from..universe_len).find(matches)
+        } else if universe_len == 0 {
+            None
+        } else {
+            (0..=from.min(universe_len - 1)).rev().find(matches)
+        }
+    }
+
+    #[test]
+    fn test_navigation() {
+        let universe = (12, 17);
+
+        let mut ones_hole = BitPageVec::all_ones(universe);
+        ones_hole.clear_range(63..65);
+        ones_hole.clear_range(300..450);
+        ones_hole.clear_bit(11, 10);
+
+        let runs = [(0, 1), (64, 536), (600, 10), (700, 20)]
+            .iter()
+            .map(|(start, len)| BitRun { start: *start, len: *len })
+            .collect();
+
+        let sparse = BitPageVec::from_ranges(vec![5..6, 63..64, 128..200, 700..720], universe);
+        let containers = pages_to_containers(sparse.pages.clone().unwrap());
+
+        // every kind... plus gaps and runs at page boundaries
+        let mut vectors = vectors(universe);
+        vectors.extend(vec![
+            BitPageVec::from_ranges(vec![0..700, 705..785], universe),
+            ones_hole,
+            BitPageVec::from_runs(runs, universe),
+            BitPageVec::from_containers(containers, universe),
+            sparse,
+        ]);
+
+        for bit_page_vec in vectors.iter() {
+            for from in 0..800 {
+                assert_eq!(
+                    bit_page_vec.next_set_bit(from),
+                    naive(bit_page_vec, from, true, true),
+                    "{:?} {}",
+                    bit_page_vec,
+                    from
+                );
+                assert_eq!(
+                    bit_page_vec.prev_set_bit(from),
+                    naive(bit_page_vec, from, true, false),
+                    "{:?} {}",
+                    bit_page_vec,
+                    from
+                );
+                assert_eq!(
+                    bit_page_vec.next_clear_bit(from),
+                    naive(bit_page_vec, from, false, true),
+                    "{:?} {}",
+                    bit_page_vec,
+                    from
+                );
+                assert_eq!(
+                    bit_page_vec.prev_clear_bit(from),
+                    naive(bit_page_vec, from, false, false),
+                    "{:?} {}",
+                    bit_page_vec,
+                    from
+                );
+            }
+
+            assert_eq!(bit_page_vec.min(), naive(bit_page_vec, 0, true, true));
+            assert_eq!(bit_page_vec.max(), naive(bit_page_vec, u64::MAX, true, false));
+        }
+    }
+}
@@ -5,31 +5,135 @@ use crate::bit_page_vec_iter::BitPageVecIter;
 
 impl BitPageVec {
     pub fn or(&mut self, second: &BitPageVec) {
-        let first = self.iter();
-        let second = second.iter();
+        let first = self.page_iter();
+        let second = second.page_iter();
 
-        *self = BitPageVecIter::or(first, second).into_bit_page_vec();
+        self.assign(BitPageVecIter::or_with(first, second, self.universe_policy()).into_bit_page_vec_with(self.compaction_policy()));
     }
 
     pub fn and(&mut self, second: &BitPageVec) {
-        let first = self.iter();
-        let second = second.iter();
+        if let Some(result) = self.containers_and(second) {
+            self.assign(result);
+            return;
+        }
 
-        *self = BitPageVecIter::and(first, second).into_bit_page_vec();
+        let first = self.page_iter();
+        let second = second.page_iter();
+
+        self.assign(BitPageVecIter::and_with(first, second, self.universe_policy()).into_bit_page_vec_with(self.compaction_policy()));
+    }
+
+    pub fn xor(&mut self, second: &BitPageVec) {
+        let first = self.page_iter();
+        let second = second.page_iter();
+
+        self.assign(BitPageVecIter::xor_with(first, second, self.universe_policy()).into_bit_page_vec_with(self.compaction_policy()));
+    }
+
+    pub fn and_not(&mut self, second: &BitPageVec) {
+        let first = self.page_iter();
+        let second = second.page_iter();
+
+        self.assign(BitPageVecIter::and_not_with(first, second, self.universe_policy()).into_bit_page_vec_with(self.compaction_policy()));
     }
 
     pub fn not(&mut self) {
-        *self = self.iter().not().into_bit_page_vec();
+        self.assign(self.page_iter().not().into_bit_page_vec_with(self.compaction_policy()));
     }
 
-    pub fn add(self, db_value: DbBitPageVec) -> BitPageVec {
+    #[allow(clippy::should_implement_trait)]
+    pub fn add(mut self, db_value: DbBitPageVec) -> BitPageVec {
         let bit_page_vec = match db_value {
             DbBitPageVec::AllZeroes => BitPageVec::all_zeros(self.last_bit_index),
             DbBitPageVec::Sparse(pages) => BitPageVec::new(BitPageVecKind::SparseWithZeroesHole, Some(pages), self.last_bit_index),
         };
 
-        let first = self.into_iter();
+        let first = self.page_iter();
+        let second = bit_page_vec.into_page_iter();
+
+        let result = BitPageVecIter::or_with(first, second, self.universe_policy()).into_bit_page_vec_with(self.compaction_policy());
+        self.assign(result);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use std::sync::Arc;
+
+    use itertools::Itertools;
+
+    use crate::bit_page_vec_fixtures::vectors;
+    use crate::{BitPageVec, BitPageVecKind, CompactionPolicy, CompactionStats, DbBitPageVec, UniversePolicy};
+
+    fn ids(bit_page_vec: &BitPageVec) -> BTreeSet<u64> {
+        bit_page_vec.iter().collect()
+    }
+
+    #[test]
+    fn test_xor_and_not() {
+        for first in vectors((40, 9)) {
+            for second in vectors((40, 9)) {
+                let (first_ids, second_ids) = (ids(&first), ids(&second));
+
+                let mut xor = first.clone();
+                xor.xor(&second);
+                assert_eq!(
+                    ids(&xor).into_iter().collect_vec(),
+                    first_ids.symmetric_difference(&second_ids).copied().collect_vec(),
+                    "{:?} ^ {:?}",
+                    first,
+                    second
+                );
+
+                let mut and_not = first.clone();
+                and_not.and_not(&second);
+                assert_eq!(
+                    ids(&and_not).into_iter().collect_vec(),
+                    first_ids.difference(&second_ids).copied().collect_vec(),
+                    "{:?} & !{:?}",
+                    first,
+                    second
+                );
+            }
+        }
+    }
+
+    // zeroes hole pages always become runs... so a default compaction would show
+    #[derive(Debug)]
+    struct AlwaysRuns;
+
+    impl CompactionPolicy for AlwaysRuns {
+        fn target(&self, stats: &CompactionStats) -> BitPageVecKind {
+            match stats.kind {
+                BitPageVecKind::SparseWithZeroesHole => BitPageVecKind::Runs,
+                kind => kind,
+            }
+        }
+    }
+
+    #[test]
+    fn test_add_keeps_settings() {
+        let policy: Arc<dyn CompactionPolicy> = Arc::new(AlwaysRuns);
+        let mut bit_page_vec = BitPageVec::all_zeros((20, 0));
+        bit_page_vec.set_range(0..600);
+        let bit_page_vec = bit_page_vec
+            .with_compaction_policy(policy.clone())
+            .with_auto_grow(true)
+            .with_universe_policy(UniversePolicy::Extend);
+        assert_eq!(bit_page_vec.kind(), &BitPageVecKind::SparseWithZeroesHole);
+
+        let mut added = bit_page_vec.add(vec![700].into_iter().collect::<DbBitPageVec>());
+        assert_eq!(added.iter().collect_vec(), (0..600).chain(vec![700]).collect_vec());
+
+        // runs (instead of pages by the default policy)... and the settings stay with the vector
+        assert_eq!(added.kind(), &BitPageVecKind::Runs);
+        assert!(Arc::ptr_eq(added.compaction_policy.as_ref().unwrap(), &policy));
+        assert!(added.auto_grow());
+        assert_eq!(added.universe_policy(), UniversePolicy::Extend);
 
-        BitPageVecIter::or(first, bit_page_vec.into_iter()).into_bit_page_vec()
+        added.insert(1000);
+        assert!(added.contains(1000));
     }
 }
@@ -0,0 +1,237 @@
+use std::mem::size_of;
+
+use itertools::{EitherOrBoth, Itertools};
+
+// @author shailendra.sharma
+use crate::bit_page::BitPageWithPosition;
+use crate::bit_page_vec::BitPageVecKind;
+use crate::bit_page_vec_containers::{containers_estimated_bytes, pages_to_containers, Container};
+use crate::bit_page_vec_runs::{count_runs, pages_to_runs, BitRun};
+use crate::{BitPage, BitPageVec, Universe};
+
+/// what optimize changed... bytes are estimates of the heap held by the vector
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct OptimizeReport {
+    pub kind_before: BitPageVecKind,
+    pub kind_after: BitPageVecKind,
+    pub bytes_before: usize,
+    pub bytes_after: usize,
+}
+
+impl OptimizeReport {
+    pub fn changed(&self) -> bool {
+        self.kind_before != self.kind_after || self.bytes_before != self.bytes_after
+    }
+}
+
+impl BitPageVec {
+    /// re-encodes the vector into its cheapest kind... redundant pages (e.g. hole valued ones or pages past the
+    /// universe) are dropped and empty or full vectors become AllZeroes or AllOnes
+    pub fn optimize(&mut self) -> OptimizeReport {
+        let (kind_before, bytes_before) = (self.kind, self.estimated_bytes());
+
+        let universe = self.universe();
+        let pages = self
+            .page_iter()
+            .into_active_pages()
+            .map(|(page_idx, bit_page)| BitPageWithPosition { page_idx, bit_page })
+            .collect_vec();
+
+        let active_bits_count = pages.iter().map(|page| page.bit_page.count_ones() as u64).sum::<u64>();
+
+        let optimized = if active_bits_count == 0 {
+            BitPageVec::all_zeros(universe)
+        } else if active_bits_count == universe.len() {
+            BitPageVec::all_ones(universe)
+        } else {
+            // every representation is estimated from the shape of the pages... only the cheapest one is built (earlier
+            // one on a tie)
+            let page_bytes = size_of::<BitPageWithPosition>();
+            let candidates = [
+                // a ones hole stores every page which is not full... it can't pay off while most bits are clear
+                (
+                    BitPageVecKind::SparseWithOnesHole,
+                    if active_bits_count >= universe.len() / 2 {
+                        Some(ones_hole_pages_len(&pages, universe) * page_bytes)
+                    } else {
+                        None
+                    },
+                ),
+                (BitPageVecKind::Runs, Some(count_runs(&pages) * size_of::<BitRun>())),
+                (BitPageVecKind::Containers, Some(containers_estimated_bytes(&pages))),
+            ];
+
+            let (cheapest, _) = candidates.iter().fold(
+                (BitPageVecKind::SparseWithZeroesHole, pages.len() * page_bytes),
+                |(cheapest, cheapest_bytes), (kind, bytes)| match bytes {
+                    Some(bytes) if *bytes < cheapest_bytes => (*kind, *bytes),
+                    _ => (cheapest, cheapest_bytes),
+                },
+            );
+
+            match cheapest {
+                BitPageVecKind::SparseWithOnesHole => BitPageVec::new(cheapest, Some(ones_hole_pages(&pages, universe)), universe),
+                BitPageVecKind::Runs => BitPageVec::from_runs(pages_to_runs(&pages), universe),
+                BitPageVecKind::Containers => BitPageVec::from_containers(pages_to_containers(pages), universe),
+                _ => BitPageVec::new(BitPageVecKind::SparseWithZeroesHole, Some(pages), universe),
+            }
+        };
+
+        self.assign(optimized);
+
+        OptimizeReport {
+            kind_before,
+            kind_after: self.kind,
+            bytes_before,
+            bytes_after: self.estimated_bytes(),
+        }
+    }
+
+    /// estimated heap bytes of the stored pages, runs or containers
+    pub fn estimated_bytes(&self) -> usize {
+        match self.kind {
+            BitPageVecKind::AllZeroes | BitPageVecKind::AllOnes => 0,
+            BitPageVecKind::SparseWithZeroesHole | BitPageVecKind::SparseWithOnesHole => self
+                .pages
+                .as_ref()
+                .map_or(0, |pages| pages.len() * size_of::<BitPageWithPosition>()),
+            BitPageVecKind::Runs => self.runs.as_ref().map_or(0, |runs| runs.len() * size_of::<BitRun>()),
+            BitPageVecKind::Containers => self.containers.as_ref().map_or(0, |containers| {
+                containers
+                    .iter()
+                    .map(|container| size_of::<Container>() + container.estimated_bytes())
+                    .sum()
+            }),
+        }
+    }
+}
+
+// number of pages ones_hole_pages would store... i.e. every page of the universe which is not full
+fn ones_hole_pages_len(pages: &[BitPageWithPosition], universe: Universe) -> usize {
+    let full_pages = pages
+        .iter()
+        .filter(|page| BitPage::is_ones(&(page.bit_page | !universe.page_mask(page.page_idx))))
+        .count();
+
+    universe.pages() - full_pages
+}
+
+// ones hole pages of (universe masked) active pages... bits past the universe count as ones so the tail page
+// is only stored when it has a hole
+fn ones_hole_pages(pages: &[BitPageWithPosition], universe: Universe) -> Vec<BitPageWithPosition> {
+    (0..universe.pages())
+        .merge_join_by(pages.iter(), |page_1_idx, page_2| page_1_idx.cmp(&page_2.page_idx))
+        .filter_map(|either| {
+            let (page_idx, bit_page) = match either {
+                EitherOrBoth::Both(page_idx, page) => (page_idx, page.bit_page),
+                EitherOrBoth::Left(page_idx) => (page_idx, BitPage::zeroes()),
+                EitherOrBoth::Right(_) => return None,
+            };
+
+            if BitPage::is_ones(&(bit_page | !universe.page_mask(page_idx))) {
+                None
+            } else {
+                Some(BitPageWithPosition { page_idx, bit_page })
+            }
+        })
+        .collect_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use std::mem::size_of;
+
+    use crate::bit_page::BitPageWithPosition;
+    use crate::bit_page_vec::BitPageVecKind;
+    use crate::bit_page_vec_containers::{containers_estimated_bytes, pages_to_containers};
+    use crate::bit_page_vec_fixtures::vectors;
+    use crate::bit_page_vec_optimize::{ones_hole_pages, ones_hole_pages_len};
+    use crate::bit_page_vec_runs::{count_runs, pages_to_runs, BitRun};
+    use crate::BitPageVec;
+
+    #[test]
+    fn test_optimize() {
+        let universe = (50, 10);
+
+        // zeroes hole vector which fills up
+        let mut bit_page_vec = BitPageVec::from_ranges(vec![10..20, 100..200], universe);
+        let ids = (0..3210).filter(|id| id % 1000 != 7).collect_vec();
+        ids.iter().for_each(|id| bit_page_vec.insert(*id));
+
+        let report = bit_page_vec.optimize();
+        assert!(report.changed());
+        assert!(report.bytes_after < report.bytes_before);
+        assert_eq!(report.kind_after, BitPageVecKind::SparseWithOnesHole);
+        assert_eq!(bit_page_vec.iter().collect_vec(), ids);
+
+        // nothing left to do
+        let report = bit_page_vec.optimize();
+        assert!(!report.changed());
+
+        // ones hole vector cleared page by page
+        let mut bit_page_vec = BitPageVec::all_ones(universe);
+        (0..3210).for_each(|id| bit_page_vec.remove(id));
+        assert_eq!(bit_page_vec.kind(), &BitPageVecKind::SparseWithOnesHole);
+
+        let report = bit_page_vec.optimize();
+        assert_eq!(report.kind_after, BitPageVecKind::AllZeroes);
+        assert_eq!(report.bytes_after, 0);
+
+        // full vector
+        let mut bit_page_vec = BitPageVec::all_zeros(universe);
+        (0..3210).for_each(|id| bit_page_vec.insert(id));
+        assert_eq!(bit_page_vec.optimize().kind_after, BitPageVecKind::AllOnes);
+
+        // a few long runs
+        let mut bit_page_vec = BitPageVec::all_zeros(universe);
+        (64..1600).chain(2000..2900).for_each(|id| bit_page_vec.insert(id));
+        assert_eq!(bit_page_vec.optimize().kind_after, BitPageVecKind::Runs);
+        assert_eq!(bit_page_vec.iter().collect_vec(), (64..1600).chain(2000..2900).collect_vec());
+    }
+
+    #[test]
+    fn test_estimates_match_built_kinds() {
+        for bit_page_vec in vectors((3000, 5)) {
+            let universe = bit_page_vec.universe();
+            let pages = bit_page_vec
+                .page_iter()
+                .into_active_pages()
+                .map(|(page_idx, bit_page)| BitPageWithPosition { page_idx, bit_page })
+                .collect_vec();
+
+            assert_eq!(ones_hole_pages_len(&pages, universe), ones_hole_pages(&pages, universe).len());
+            assert_eq!(
+                count_runs(&pages) * size_of::<BitRun>(),
+                BitPageVec::from_runs(pages_to_runs(&pages), universe).estimated_bytes()
+            );
+            assert_eq!(
+                containers_estimated_bytes(&pages),
+                BitPageVec::from_containers(pages_to_containers(pages.clone()), universe).estimated_bytes()
+            );
+        }
+    }
+
+    #[test]
+    fn test_optimize_single_bit_of_a_large_universe() {
+        // nothing is built per page of the universe
+        let mut bit_page_vec = BitPageVec::all_zeros((1 << 26, 0));
+        bit_page_vec.insert(123_456_789);
+
+        // a single page is already the cheapest
+        let report = bit_page_vec.optimize();
+        assert!(!report.changed());
+        assert_eq!(report.kind_after, BitPageVecKind::SparseWithZeroesHole);
+        assert_eq!(bit_page_vec.iter().collect_vec(), vec![123_456_789]);
+
+        // a few sparse ids per page
+        let mut bit_page_vec = BitPageVec::all_zeros((1 << 26, 0));
+        (0..10_000u64).for_each(|id| bit_page_vec.insert(id * 131));
+
+        let report = bit_page_vec.optimize();
+        assert_eq!(report.kind_after, BitPageVecKind::Containers);
+        assert!(report.bytes_after < report.bytes_before);
+        assert_eq!(bit_page_vec.active_bits_count(), 10_000);
+    }
+}
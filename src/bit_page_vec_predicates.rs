@@ -0,0 +1,75 @@
+// @author shailendra.sharma
+use crate::bit_page_vec_iter::BitPageVecIter;
+use crate::BitPageVec;
+
+impl<'a> BitPageVecIter<'a> {
+    /// true if the stream has any active bit... stops at the first non empty page
+    pub fn has_active_bits(self) -> bool {
+        self.into_active_pages().next().is_some()
+    }
+}
+
+impl BitPageVec {
+    /// true if self and second share at least one active bit
+    pub fn intersects(&self, second: &BitPageVec) -> bool {
+        BitPageVecIter::and_with(self.page_iter(), second.page_iter(), self.universe_policy()).has_active_bits()
+    }
+
+    pub fn is_disjoint(&self, second: &BitPageVec) -> bool {
+        !self.intersects(second)
+    }
+
+    /// true if every active bit of self is active in second
+    pub fn is_subset(&self, second: &BitPageVec) -> bool {
+        !BitPageVecIter::and_not_with(self.page_iter(), second.page_iter(), self.universe_policy()).has_active_bits()
+    }
+
+    pub fn is_superset(&self, second: &BitPageVec) -> bool {
+        second.is_subset(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use crate::bit_page_vec_fixtures::vectors;
+    use crate::BitPageVec;
+
+    #[test]
+    fn test_predicates() {
+        let mut vectors = vectors((30, 0));
+
+        // subsets of the ones hole and of the runs vectors... short of a single id
+        for index in [vectors.len() - 1, 3] {
+            let mut with_gap = vectors[index].clone();
+            with_gap.remove(1500);
+            vectors.push(with_gap);
+        }
+        vectors.push(BitPageVec::from_ranges(vec![1200..1300, 1500..1501], (30, 0)));
+
+        for first in vectors.iter() {
+            for second in vectors.iter() {
+                let first_ids = first.iter().collect::<BTreeSet<_>>();
+                let second_ids = second.iter().collect::<BTreeSet<_>>();
+
+                assert_eq!(
+                    first.intersects(second),
+                    !first_ids.is_disjoint(&second_ids),
+                    "{:?} {:?}",
+                    first,
+                    second
+                );
+                assert_eq!(first.is_disjoint(second), first_ids.is_disjoint(&second_ids));
+                assert_eq!(
+                    first.is_subset(second),
+                    first_ids.is_subset(&second_ids),
+                    "{:?} {:?}",
+                    first,
+                    second
+                );
+                assert_eq!(first.is_superset(second), first_ids.is_superset(&second_ids));
+            }
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use std::cmp::{max, min};
+use std::ops::Range;
+
+// @author shailendra.sharma
+use crate::bit_page::{split_bit_index, zero_masks, BitPageWithPosition};
+use crate::bit_page_vec::BitPageVecKind;
+use crate::bit_page_vec_containers::containers_to_pages;
+use crate::bit_page_vec_runs::apply_range_op_runs;
+use crate::{BitPage, BitPageVec};
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum RangeOp {
+    Set,
+    Clear,
+    Flip,
+}
+
+impl RangeOp {
+    #[inline]
+    fn apply(self, bit_page: u64, mask: u64) -> u64 {
+        match self {
+            RangeOp::Set => bit_page | mask,
+            RangeOp::Clear => bit_page & !mask,
+            RangeOp::Flip => bit_page ^ mask,
+        }
+    }
+
+    // whether a fully covered page always ends up with the hole value
+    #[inline]
+    fn fills_with(self, hole: u64) -> bool {
+        match self {
+            RangeOp::Set => BitPage::is_ones(&hole),
+            RangeOp::Clear => BitPage::is_zeroes(&hole),
+            RangeOp::Flip => false,
+        }
+    }
+}
+
+impl BitPageVec {
+    /// sets all bits in the range... range is clipped to last_bit_index
+    pub fn set_range(&mut self, range: Range<u64>) {
+        self.apply_range(range, RangeOp::Set);
+    }
+
+    /// clears all bits in the range... range is clipped to last_bit_index
+    pub fn clear_range(&mut self, range: Range<u64>) {
+        self.apply_range(range, RangeOp::Clear);
+    }
+
+    /// flips all bits in the range... range is clipped to last_bit_index
+    pub fn flip_range(&mut self, range: Range<u64>) {
+        self.apply_range(range, RangeOp::Flip);
+    }
+
+    pub fn from_ranges<I>(ranges: I, last_bit_index: (usize, usize)) -> BitPageVec
+    where
+        I: IntoIterator<Item = Range<u64>>,
+    {
+        let bit_page_vec = BitPageVec::all_zeros(last_bit_index);
+        let ranges = coalesce_ranges(ranges.into_iter().map(|range| bit_page_vec.clip_range(range)));
+
+        BitPageVec::from_sparse_pages(BitPage::zeroes(), pages_from_sorted_ranges(ranges), last_bit_index)
+    }
+
+    fn apply_range(&mut self, range: Range<u64>, op: RangeOp) {
+        let range = self.clip_range(range);
+        if range.start >= range.end {
+            return;
+        }
+
+        let (hole, pages) = match self.kind {
+            BitPageVecKind::Runs => {
+                let runs = apply_range_op_runs(self.runs.take().unwrap_or_default(), range, op);
+                self.assign(BitPageVec::from_runs(runs, self.last_bit_index));
+                return;
+            }
+            BitPageVecKind::Containers => {
+                let pages = containers_to_pages(self.containers.take().unwrap_or_default());
+                let pages = apply_range_op(pages, BitPage::zeroes(), range, op);
+
+                // zeroes hole result is re-packed... so sparse regions stay as containers
+                self.assign(BitPageVec::from_active_pages(pages, self.last_bit_index));
+                return;
+            }
+            BitPageVecKind::AllZeroes => (BitPage::zeroes(), Vec::new()),
+            BitPageVecKind::SparseWithZeroesHole => (BitPage::zeroes(), self.pages.take().unwrap_or_default()),
+            BitPageVecKind::AllOnes => (BitPage::ones(), Vec::new()),
+            BitPageVecKind::SparseWithOnesHole => (BitPage::ones(), self.pages.take().unwrap_or_default()),
+        };
+
+        let pages = apply_range_op(pages, hole, range, op);
+
+        self.assign(BitPageVec::from_sparse_pages(hole, pages, self.last_bit_index));
+    }
+
+    pub(crate) fn clip_range(&self, range: Range<u64>) -> Range<u64> {
+        let end = min(range.end, self.universe_len());
+
+        min(range.start, end)..end
+    }
+}
+
+// mask of bits of page_idx covered by the range [start, end]... both ends are inclusive (page_idx, bit_idx) positions
+#[inline]
+pub(crate) fn range_mask(page_idx: usize, start: (usize, usize), end: (usize, usize)) -> u64 {
+    let mut mask = BitPage::ones();
+
+    if page_idx == start.0 {
+        mask &= !zero_masks()[start.1];
+    }
+
+    if page_idx == end.0 && end.1 + 1 < BitPage::MAX_BITS {
+        mask &= zero_masks()[end.1 + 1];
+    }
+
+    mask
+}
+
+// applies op over a non-empty range to pages of a sparse vector whose missing pages have `hole` value...
+// partial masks are used for boundary pages and whole page masks for the interior
+pub(crate) fn apply_range_op(pages: Vec<BitPageWithPosition>, hole: u64, range: Range<u64>, op: RangeOp) -> Vec<BitPageWithPosition> {
+    let start = split_bit_index(range.start);
+    let end = split_bit_index(range.end - 1);
+
+    let mut result = Vec::with_capacity(pages.len());
+    let mut pages = pages.into_iter().peekable();
+
+    // pages before the range are kept as is
+    while let Some(page) = pages.next_if(|page| page.page_idx < start.0) {
+        result.push(page);
+    }
+
+    let apply = |result: &mut Vec<BitPageWithPosition>, page_idx: usize, bit_page: u64| {
+        let bit_page = op.apply(bit_page, range_mask(page_idx, start, end));
+        if bit_page != hole {
+            result.push(BitPageWithPosition { page_idx, bit_page });
+        }
+    };
+
+    if op.fills_with(hole) {
+        // interior pages all collapse into the hole... only boundary pages need a look
+        for page_idx in (start.0..=end.0).filter(|page_idx| *page_idx == start.0 || *page_idx == end.0) {
+            while pages.next_if(|page| page.page_idx < page_idx).is_some() {}
+
+            let bit_page = pages.next_if(|page| page.page_idx == page_idx).map_or(hole, |page| page.bit_page);
+            apply(&mut result, page_idx, bit_page);
+        }
+    } else {
+        for page_idx in start.0..=end.0 {
+            let bit_page = pages.next_if(|page| page.page_idx == page_idx).map_or(hole, |page| page.bit_page);
+            apply(&mut result, page_idx, bit_page);
+        }
+    }
+
+    // pages after the range are kept as is
+    result.extend(pages);
+
+    result
+}
+
+// sorts ranges and merges overlapping or adjacent ones... empty ranges are dropped
+pub(crate) fn coalesce_ranges<I>(ranges: I) -> Vec<Range<u64>>
+where
+    I: IntoIterator<Item = Range<u64>>,
+{
+    let mut ranges = ranges.into_iter().filter(|range| range.start < range.end).collect::<Vec<_>>();
+    ranges.sort_unstable_by_key(|range| range.start);
+
+    let mut result: Vec<Range<u64>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match result.last_mut() {
+            Some(last) if range.start <= last.end => last.end = max(last.end, range.end),
+            _ => result.push(range),
+        }
+    }
+
+    result
+}
+
+// ranges must be sorted, non-empty and non-overlapping (see coalesce_ranges)
+pub(crate) fn pages_from_sorted_ranges(ranges: Vec<Range<u64>>) -> Vec<BitPageWithPosition> {
+    let mut pages: Vec<BitPageWithPosition> = Vec::new();
+
+    for range in ranges {
+        let start = split_bit_index(range.start);
+        let end = split_bit_index(range.end - 1);
+
+        for page_idx in start.0..=end.0 {
+            let mask = range_mask(page_idx, start, end);
+            match pages.last_mut() {
+                Some(page) if page.page_idx == page_idx => page.bit_page |= mask,
+                _ => pages.push(BitPageWithPosition { page_idx, bit_page: mask }),
+            }
+        }
+    }
+
+    pages
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::bit_page_vec::BitPageVecKind;
+    use crate::BitPageVec;
+
+    #[test]
+    fn test_set_range() {
+        let mut bit_page_vec = BitPageVec::all_zeros((100, 0));
+        bit_page_vec.set_range(60..200);
+
+        assert!(matches!(bit_page_vec.kind(), BitPageVecKind::SparseWithZeroesHole));
+        assert_eq!(bit_page_vec.iter().collect_vec(), (60..200).collect_vec());
+
+        // more than half of the universe is cheaper with ones hole
+        bit_page_vec.set_range(1000..6000);
+        assert!(matches!(bit_page_vec.kind(), BitPageVecKind::SparseWithOnesHole));
+        assert_eq!(bit_page_vec.active_bits_count(), 140 + 5000);
+        assert!(bit_page_vec.contains(5999));
+        assert!(!bit_page_vec.contains(999));
+
+        bit_page_vec.set_range(0..6400);
+        assert!(matches!(bit_page_vec.kind(), BitPageVecKind::AllOnes));
+    }
+
+    #[test]
+    fn test_clear_and_flip_range() {
+        let mut bit_page_vec = BitPageVec::all_ones((10, 0));
+        bit_page_vec.clear_range(10..630);
+        assert_eq!(bit_page_vec.iter().collect_vec(), (0..10).chain(630..640).collect_vec());
+
+        bit_page_vec.flip_range(5..15);
+        assert_eq!(
+            bit_page_vec.iter().collect_vec(),
+            (0..5).chain(10..15).chain(630..640).collect_vec()
+        );
+
+        bit_page_vec.clear_range(0..640);
+        assert!(matches!(bit_page_vec.kind(), BitPageVecKind::AllZeroes));
+    }
+
+    #[test]
+    fn test_from_ranges() {
+        let bit_page_vec = BitPageVec::from_ranges(vec![100..130, 0..3, 120..140, 2..5, 700..800], (10, 0));
+
+        assert_eq!(bit_page_vec.iter().collect_vec(), (0..5).chain(100..140).collect_vec());
+    }
+}
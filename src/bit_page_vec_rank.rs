@@ -0,0 +1,379 @@
+use std::cmp::min;
+use std::ops::Range;
+
+// @author shailendra.sharma
+use crate::bit_page::{join_bit_index, split_bit_index, BitPageWithPosition};
+use crate::bit_page_vec::BitPageVecKind;
+use crate::bit_page_vec_containers::{Container, CONTAINER_PAGES};
+use crate::bit_page_vec_runs::BitRun;
+use crate::{BitPage, BitPageVec};
+
+// number of stored pages (or runs) covered by one entry of the rank index
+const RANK_BLOCK_PAGES: usize = 64;
+
+/// cumulative popcount block index over the pages of a BitPageVec... makes repeated rank / select calls sub-linear
+pub struct BitPageVecRankIndex<'a> {
+    bit_page_vec: &'a BitPageVec,
+    // weight of all stored pages before every block of RANK_BLOCK_PAGES pages
+    blocks: Vec<u64>,
+}
+
+impl<'a> BitPageVecRankIndex<'a> {
+    pub fn rank(&self, index: u64) -> u64 {
+        rank(self.bit_page_vec, Some(&self.blocks), index)
+    }
+
+    pub fn select(&self, n: u64) -> Option<u64> {
+        select(self.bit_page_vec, Some(&self.blocks), n)
+    }
+
+    pub fn count_in_range(&self, range: Range<u64>) -> u64 {
+        count_in_range(self.bit_page_vec, Some(&self.blocks), range)
+    }
+}
+
+impl BitPageVec {
+    /// number of active bits below index
+    pub fn rank(&self, index: u64) -> u64 {
+        rank(self, None, index)
+    }
+
+    /// index of the n-th (zero based) active bit
+    pub fn select(&self, n: u64) -> Option<u64> {
+        select(self, None, n)
+    }
+
+    pub fn count_in_range(&self, range: Range<u64>) -> u64 {
+        count_in_range(self, None, range)
+    }
+
+    pub fn rank_index(&self) -> BitPageVecRankIndex<'_> {
+        let blocks = match (self.kind, self.pages.as_ref()) {
+            (BitPageVecKind::SparseWithZeroesHole, Some(pages)) | (BitPageVecKind::SparseWithOnesHole, Some(pages)) => {
+                let mut blocks = Vec::with_capacity(pages.len() / RANK_BLOCK_PAGES + 1);
+
+                let mut weight = 0;
+                for chunk in pages.chunks(RANK_BLOCK_PAGES) {
+                    blocks.push(weight);
+                    weight += chunk.iter().map(|page| page_weight(self.kind, page.bit_page)).sum::<u64>();
+                }
+
+                blocks
+            }
+            (BitPageVecKind::Runs, _) => {
+                let runs = self.runs.as_deref().unwrap_or(&[]);
+                let mut blocks = Vec::with_capacity(runs.len() / RANK_BLOCK_PAGES + 1);
+
+                let mut weight = 0;
+                for chunk in runs.chunks(RANK_BLOCK_PAGES) {
+                    blocks.push(weight);
+                    weight += chunk.iter().map(|run| run.len()).sum::<u64>();
+                }
+
+                blocks
+            }
+            (BitPageVecKind::Containers, _) => {
+                // containers already cover many pages... one entry per container
+                let containers = self.containers.as_deref().unwrap_or(&[]);
+                containers
+                    .iter()
+                    .scan(0, |weight, container| {
+                        let before = *weight;
+                        *weight += container.count_ones() as u64;
+                        Some(before)
+                    })
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+
+        BitPageVecRankIndex {
+            bit_page_vec: self,
+            blocks,
+        }
+    }
+}
+
+// active bits of a page for zeroes hole and inactive bits of a page for ones hole
+#[inline]
+fn page_weight(kind: BitPageVecKind, bit_page: u64) -> u64 {
+    match kind {
+        BitPageVecKind::SparseWithOnesHole => bit_page.count_zeros() as u64,
+        _ => bit_page.count_ones() as u64,
+    }
+}
+
+// weight of stored pages before position
+fn prefix_weight(kind: BitPageVecKind, pages: &[BitPageWithPosition], blocks: Option<&[u64]>, position: usize) -> u64 {
+    let (start, weight) = match blocks {
+        Some(blocks) if !blocks.is_empty() => {
+            let block = min(position / RANK_BLOCK_PAGES, blocks.len() - 1);
+            (block * RANK_BLOCK_PAGES, blocks[block])
+        }
+        _ => (0, 0),
+    };
+
+    weight
+        + pages[start..position]
+            .iter()
+            .map(|page| page_weight(kind, page.bit_page))
+            .sum::<u64>()
+}
+
+// length of runs before position
+fn prefix_run_len(runs: &[BitRun], blocks: Option<&[u64]>, position: usize) -> u64 {
+    let (start, len) = match blocks {
+        Some(blocks) if !blocks.is_empty() => {
+            let block = min(position / RANK_BLOCK_PAGES, blocks.len() - 1);
+            (block * RANK_BLOCK_PAGES, blocks[block])
+        }
+        _ => (0, 0),
+    };
+
+    len + runs[start..position].iter().map(|run| run.len()).sum::<u64>()
+}
+
+// active bits of containers before position
+fn prefix_container_count(containers: &[Container], blocks: Option<&[u64]>, position: usize) -> u64 {
+    match blocks {
+        Some(blocks) if position < blocks.len() => blocks[position],
+        Some(blocks) if !blocks.is_empty() => blocks[blocks.len() - 1] + containers[blocks.len() - 1].count_ones() as u64,
+        _ => containers[..position].iter().map(|container| container.count_ones() as u64).sum(),
+    }
+}
+
+// active bits before the start of the stored page... prefix is the weight of all stored pages before it
+#[inline]
+fn active_before(kind: BitPageVecKind, page_idx: usize, prefix: u64) -> u64 {
+    match kind {
+        BitPageVecKind::SparseWithOnesHole => join_bit_index(page_idx, 0) - prefix,
+        _ => prefix,
+    }
+}
+
+fn rank(bit_page_vec: &BitPageVec, blocks: Option<&[u64]>, index: u64) -> u64 {
+    let index = min(index, bit_page_vec.universe_len());
+
+    match (bit_page_vec.kind, bit_page_vec.pages.as_ref()) {
+        (BitPageVecKind::Runs, _) => {
+            let runs = bit_page_vec.runs.as_deref().unwrap_or(&[]);
+
+            // runs ending at or before index are fully counted
+            let position = runs.partition_point(|run| run.end() <= index);
+            let partial = runs.get(position).map_or(0, |run| index.saturating_sub(run.start()));
+
+            prefix_run_len(runs, blocks, position) + partial
+        }
+        (BitPageVecKind::Containers, _) => {
+            let containers = bit_page_vec.containers.as_deref().unwrap_or(&[]);
+            let region_idx = split_bit_index(index).0 / CONTAINER_PAGES;
+
+            // containers of earlier regions are fully counted
+            let position = containers.partition_point(|container| container.region_idx() < region_idx);
+            let partial = containers
+                .get(position)
+                .filter(|container| container.region_idx() == region_idx)
+                .map_or(0, |container| container.rank(index));
+
+            prefix_container_count(containers, blocks, position) + partial
+        }
+        (BitPageVecKind::AllZeroes, _) | (BitPageVecKind::SparseWithZeroesHole, None) => 0,
+        (BitPageVecKind::AllOnes, _) | (BitPageVecKind::SparseWithOnesHole, None) => index,
+        (kind, Some(pages)) => {
+            let (page_idx, bit_idx) = split_bit_index(index);
+
+            let (position, bit_page) = match pages.binary_search_by(|probe| probe.page_idx.cmp(&page_idx)) {
+                Ok(matching_index) => (matching_index, Some(pages[matching_index].bit_page)),
+                Err(insertion_index) => (insertion_index, None),
+            };
+
+            let prefix = prefix_weight(kind, pages, blocks, position);
+            let hole_rank = match kind {
+                BitPageVecKind::SparseWithOnesHole => bit_idx as u64,
+                _ => 0,
+            };
+
+            active_before(kind, page_idx, prefix) + bit_page.map_or(hole_rank, |bit_page| BitPage::rank(&bit_page, bit_idx) as u64)
+        }
+    }
+}
+
+fn select(bit_page_vec: &BitPageVec, blocks: Option<&[u64]>, n: u64) -> Option<u64> {
+    let universe_len = bit_page_vec.universe_len();
+
+    let index = match (bit_page_vec.kind, bit_page_vec.pages.as_ref()) {
+        (BitPageVecKind::Runs, _) => {
+            let runs = bit_page_vec.runs.as_deref().unwrap_or(&[]);
+
+            // start from the last block which begins at or before the n-th active bit
+            let (mut position, mut before) = match blocks {
+                Some(blocks) if !blocks.is_empty() => {
+                    let block = blocks.partition_point(|weight| *weight <= n).saturating_sub(1);
+                    (block * RANK_BLOCK_PAGES, blocks[block])
+                }
+                _ => (0, 0),
+            };
+
+            let mut result = None;
+            while position < runs.len() {
+                let run = runs[position];
+                if n < before + run.len() {
+                    result = Some(run.start() + (n - before));
+                    break;
+                }
+
+                before += run.len();
+                position += 1;
+            }
+
+            result
+        }
+        (BitPageVecKind::Containers, _) => {
+            let containers = bit_page_vec.containers.as_deref().unwrap_or(&[]);
+
+            // start from the last container which begins at or before the n-th active bit
+            let (mut position, mut before) = match blocks {
+                Some(blocks) if !blocks.is_empty() => {
+                    let position = blocks.partition_point(|weight| *weight <= n).saturating_sub(1);
+                    (position, blocks[position])
+                }
+                _ => (0, 0),
+            };
+
+            let mut result = None;
+            while position < containers.len() {
+                let count = containers[position].count_ones() as u64;
+                if n < before + count {
+                    result = containers[position].select(n - before);
+                    break;
+                }
+
+                before += count;
+                position += 1;
+            }
+
+            result
+        }
+        (BitPageVecKind::AllZeroes, _) | (BitPageVecKind::SparseWithZeroesHole, None) => None,
+        (BitPageVecKind::AllOnes, _) | (BitPageVecKind::SparseWithOnesHole, None) => Some(n),
+        (kind, Some(pages)) => {
+            // start from the last block which begins at or before the n-th active bit
+            let (mut position, mut prefix) = match blocks {
+                Some(blocks) if !blocks.is_empty() => {
+                    let (mut low, mut high) = (0, blocks.len());
+                    while low < high {
+                        let mid = (low + high) / 2;
+                        if active_before(kind, pages[mid * RANK_BLOCK_PAGES].page_idx, blocks[mid]) <= n {
+                            low = mid + 1;
+                        } else {
+                            high = mid;
+                        }
+                    }
+                    let block = low.saturating_sub(1);
+
+                    (block * RANK_BLOCK_PAGES, blocks[block])
+                }
+                _ => (0, 0),
+            };
+
+            // end of the previous stored page and active bits till there
+            let mut previous = if position == 0 {
+                (0, 0)
+            } else {
+                let page_idx = pages[position - 1].page_idx + 1;
+                (page_idx, active_before(kind, page_idx, prefix))
+            };
+
+            let mut result = None;
+            while position < pages.len() {
+                let BitPageWithPosition { page_idx, bit_page } = pages[position];
+                let before = active_before(kind, page_idx, prefix);
+
+                if before > n {
+                    // n-th active bit is in the hole (of ones) before this page
+                    result = Some(join_bit_index(previous.0, 0) + (n - previous.1));
+                    break;
+                }
+
+                let count = bit_page.count_ones() as u64;
+                if n < before + count {
+                    result = BitPage::select(&bit_page, (n - before) as u32).map(|bit_idx| join_bit_index(page_idx, bit_idx));
+                    break;
+                }
+
+                prefix += page_weight(kind, bit_page);
+                position += 1;
+                previous = (page_idx + 1, before + count);
+            }
+
+            match (result, kind) {
+                (None, BitPageVecKind::SparseWithOnesHole) => Some(join_bit_index(previous.0, 0) + (n - previous.1)),
+                (result, _) => result,
+            }
+        }
+    };
+
+    index.filter(|index| *index < universe_len)
+}
+
+fn count_in_range(bit_page_vec: &BitPageVec, blocks: Option<&[u64]>, range: Range<u64>) -> u64 {
+    if range.start >= range.end {
+        return 0;
+    }
+
+    rank(bit_page_vec, blocks, range.end) - rank(bit_page_vec, blocks, range.start)
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::{BitPageVec, BitPageVecRankIndex};
+
+    fn assert_rank_select(bit_page_vec: &BitPageVec) {
+        let ids = bit_page_vec.iter().collect_vec();
+        let rank_index: BitPageVecRankIndex<'_> = bit_page_vec.rank_index();
+        let universe_len = 64 * bit_page_vec.last_bit_index().0 as u64 + bit_page_vec.last_bit_index().1 as u64;
+
+        for index in (0..=universe_len).step_by(7) {
+            let expected = ids.iter().filter(|id| **id < index).count() as u64;
+            assert_eq!(bit_page_vec.rank(index), expected, "rank({})", index);
+            assert_eq!(rank_index.rank(index), expected, "indexed rank({})", index);
+        }
+
+        for (n, id) in ids.iter().enumerate() {
+            assert_eq!(bit_page_vec.select(n as u64), Some(*id), "select({})", n);
+            assert_eq!(rank_index.select(n as u64), Some(*id), "indexed select({})", n);
+        }
+        assert_eq!(bit_page_vec.select(ids.len() as u64), None);
+        assert_eq!(rank_index.select(ids.len() as u64), None);
+
+        let expected = ids.iter().filter(|id| (100..5000).contains(*id)).count() as u64;
+        assert_eq!(bit_page_vec.count_in_range(100..5000), expected);
+        assert_eq!(rank_index.count_in_range(100..5000), expected);
+    }
+
+    #[test]
+    fn test_rank_select_zeroes_hole() {
+        let bit_page_vec = (0..20_000).filter(|id| id % 3 == 0 || id % 1000 < 70).collect::<BitPageVec>();
+        assert_rank_select(&bit_page_vec);
+    }
+
+    #[test]
+    fn test_rank_select_ones_hole() {
+        let mut bit_page_vec = BitPageVec::all_ones((300, 5));
+        for id in (0..19_205).filter(|id| id % 5 == 0) {
+            bit_page_vec.remove(id);
+        }
+        bit_page_vec.clear_range(6000..6400);
+        bit_page_vec.clear_range(0..64);
+
+        assert_rank_select(&bit_page_vec);
+    }
+
+    #[test]
+    fn test_rank_select_all() {
+        assert_rank_select(&BitPageVec::all_ones((3, 7)));
+        assert_rank_select(&BitPageVec::all_zeros((3, 7)));
+    }
+}
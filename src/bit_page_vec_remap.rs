@@ -0,0 +1,145 @@
+use itertools::{EitherOrBoth, Itertools};
+
+// @author shailendra.sharma
+use crate::bit_page::join_bit_index;
+use crate::bit_page_vec_ids::pages_from_sorted_ids;
+use crate::bit_page_vec_iter::{merge_cmp, BitPageVecIter, PageItem};
+use crate::{BitPage, BitPageVec, Universe};
+
+// marks an id dropped by remap_with
+pub const REMAP_DROPPED: u64 = u64::MAX;
+
+impl BitPageVec {
+    /// moves active bits into the compacted id space of a deletion map... deleted ids vanish and
+    /// every other id moves down by the number of deleted ids before it
+    pub fn remap(&self, deleted: &BitPageVec) -> BitPageVec {
+        let universe_len = self.universe_len();
+        let deleted_count = deleted.count_in_range(0..universe_len);
+
+        let ids = remap_ids(
+            self.page_iter().into_active_pages(),
+            deleted.page_iter().with_universe(self.universe()).into_active_pages(),
+        );
+
+        let universe = Universe::new(universe_len - deleted_count);
+        BitPageVecIter::compact_sparse_with_zeroes_hole(pages_from_sorted_ids(ids), universe)
+    }
+
+    /// moves every active id to mapping[id]... ids mapped to REMAP_DROPPED (or past the end of mapping) are dropped.
+    /// universe ends with the largest id of mapping
+    pub fn remap_with(&self, mapping: &[u64]) -> BitPageVec {
+        let universe = mapping.iter().filter(|id| **id != REMAP_DROPPED).max().map_or(0, |id| id + 1);
+
+        let mut ids = remap_ids_with(self.iter(), mapping).collect::<Vec<_>>();
+        ids.sort_unstable();
+        ids.dedup();
+
+        // compacted once against the final universe... a ones hole must not cover the tail past the largest mapped id
+        BitPageVecIter::compact_sparse_with_zeroes_hole(pages_from_sorted_ids(ids), Universe::new(universe))
+    }
+}
+
+// ids of active pages moved down by the number of deleted ids before them... deleted ids are skipped
+pub(crate) fn remap_ids<'a, P, D>(pages: P, deleted: D) -> impl Iterator<Item = u64> + 'a
+where
+    P: Iterator<Item = PageItem> + 'a,
+    D: Iterator<Item = PageItem> + 'a,
+{
+    pages
+        .merge_join_by(deleted, merge_cmp)
+        .scan(0u64, |deleted_before, either| {
+            let (page_idx, bit_page, deleted_page) = match either {
+                EitherOrBoth::Both((page_idx, bit_page), (_, deleted_page)) => (page_idx, bit_page, deleted_page),
+                EitherOrBoth::Left((page_idx, bit_page)) => (page_idx, bit_page, BitPage::zeroes()),
+                EitherOrBoth::Right((page_idx, deleted_page)) => (page_idx, BitPage::zeroes(), deleted_page),
+            };
+
+            let before = *deleted_before;
+            *deleted_before += deleted_page.count_ones() as u64;
+
+            let iter = BitPage::active_bits(bit_page & !deleted_page)
+                .map(move |bit_idx| join_bit_index(page_idx, bit_idx) - before - BitPage::rank(&deleted_page, bit_idx) as u64);
+
+            Some(iter)
+        })
+        .flatten()
+}
+
+pub(crate) fn remap_ids_with<'a, I>(ids: I, mapping: &'a [u64]) -> impl Iterator<Item = u64> + 'a
+where
+    I: Iterator<Item = u64> + 'a,
+{
+    ids.filter_map(move |id| mapping.get(id as usize).copied())
+        .filter(|id| *id != REMAP_DROPPED)
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::bit_page_vec_remap::REMAP_DROPPED;
+    use crate::BitPageVec;
+
+    #[test]
+    fn test_remap() {
+        let deleted = BitPageVec::from_ranges(vec![3..4, 60..70, 200..500, 639..640], (10, 0));
+
+        let mut ones_hole = BitPageVec::all_ones((10, 0));
+        ones_hole.clear_range(100..150);
+
+        let vectors = [
+            BitPageVec::all_zeros((10, 0)),
+            BitPageVec::all_ones((10, 0)),
+            BitPageVec::from_ranges(vec![0..10, 65..130, 400..700], (10, 0)),
+            ones_hole,
+        ];
+
+        let live = (0..640u64).filter(|id| !deleted.contains(*id)).collect_vec();
+        for bit_page_vec in vectors.iter() {
+            let remapped = bit_page_vec.remap(&deleted);
+            let expected = live
+                .iter()
+                .enumerate()
+                .filter(|(_, id)| bit_page_vec.contains(**id))
+                .map(|(new_id, _)| new_id as u64)
+                .collect_vec();
+
+            assert_eq!(remapped.universe().len(), live.len() as u64);
+            assert_eq!(remapped.iter().collect_vec(), expected, "{:?}", bit_page_vec);
+        }
+    }
+
+    #[test]
+    fn test_remap_with() {
+        let bit_page_vec = BitPageVec::from_ranges(vec![0..4, 8..9], (1, 0));
+
+        // reverse the first 10 ids... and drop id 2
+        let mut mapping = (0..10u64).rev().collect_vec();
+        mapping[2] = REMAP_DROPPED;
+
+        let remapped = bit_page_vec.remap_with(&mapping);
+        assert_eq!(remapped.universe().len(), 10);
+        assert_eq!(remapped.iter().collect_vec(), vec![1, 6, 8, 9]);
+    }
+
+    #[test]
+    fn test_remap_with_dense_past_largest_mapped_id() {
+        // dense enough to be compacted into a ones hole
+        let bit_page_vec = (0..1_000_000u64).filter(|id| id % 8 != 0).collect::<BitPageVec>();
+        assert_eq!(bit_page_vec.active_bits_count(), 875_000);
+
+        // identity... plus one unused entry which widens the universe far past the largest mapped id
+        let mut mapping = (0..=1_000_000u64).collect_vec();
+        mapping[1_000_000] = 3_000_000;
+
+        let remapped = bit_page_vec.remap_with(&mapping);
+        assert_eq!(remapped.universe().len(), 3_000_001);
+        assert_eq!(remapped.active_bits_count(), 875_000);
+        assert!(remapped.contains(999_999));
+        assert!(!remapped.contains(999_992));
+        assert!(!remapped.contains(1_000_000));
+        assert!(!remapped.contains(2_000_000));
+        assert!(!remapped.contains(3_000_000));
+        assert_eq!(remapped.iter().last(), Some(999_999));
+    }
+}
@@ -0,0 +1,150 @@
+// @author shailendra.sharma
+use crate::bit_page::{join_bit_index, BitPageWithPosition};
+use crate::bit_page_vec::BitPageVecKind;
+use crate::{BitPage, BitPageVec};
+
+/// value of the bits added when a vector grows
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GrowFill {
+    Zeroes,
+    Ones,
+}
+
+impl BitPageVec {
+    /// grows or truncates the vector to new_last_bit_index... fill is used for the added bits only
+    pub fn resize(&mut self, new_last_bit_index: (usize, usize), fill: GrowFill) {
+        if new_last_bit_index < self.last_bit_index {
+            self.truncate(new_last_bit_index);
+        } else {
+            self.grow_to(new_last_bit_index, fill);
+        }
+    }
+
+    /// grows the vector to new_last_bit_index (no-op if it is not larger)
+    pub fn grow_to(&mut self, new_last_bit_index: (usize, usize), fill: GrowFill) {
+        if new_last_bit_index <= self.last_bit_index {
+            return;
+        }
+
+        let old_len = self.universe_len();
+        self.last_bit_index = new_last_bit_index;
+
+        // nothing is stored past the old end of a zeroes hole vector... zeroes fill is free
+        let zeroes_hole = match self.kind {
+            BitPageVecKind::AllZeroes => true,
+            BitPageVecKind::SparseWithZeroesHole | BitPageVecKind::Runs | BitPageVecKind::Containers => self.stored_end() <= old_len,
+            BitPageVecKind::AllOnes | BitPageVecKind::SparseWithOnesHole => false,
+        };
+
+        match fill {
+            GrowFill::Zeroes if zeroes_hole => {}
+            GrowFill::Zeroes => self.clear_range(old_len..self.universe_len()),
+            GrowFill::Ones => self.set_range(old_len..self.universe_len()),
+        }
+    }
+
+    /// truncates the vector to new_last_bit_index (no-op if it is not smaller)... bits past it are dropped
+    pub fn truncate(&mut self, new_last_bit_index: (usize, usize)) {
+        if new_last_bit_index >= self.last_bit_index {
+            return;
+        }
+
+        match self.kind {
+            BitPageVecKind::AllZeroes | BitPageVecKind::AllOnes => self.last_bit_index = new_last_bit_index,
+            _ => {
+                let pages = self
+                    .page_iter()
+                    .with_universe(new_last_bit_index)
+                    .into_active_pages()
+                    .map(|(page_idx, bit_page)| BitPageWithPosition { page_idx, bit_page })
+                    .collect::<Vec<_>>();
+
+                self.assign(BitPageVec::from_active_pages(pages, new_last_bit_index));
+            }
+        }
+    }
+
+    // end (exclusive) of the stored active bits of a zeroes hole vector... bits set past last_bit_index are included
+    fn stored_end(&self) -> u64 {
+        let end_of_page =
+            |(page_idx, bit_page): (usize, u64)| join_bit_index(page_idx, BitPage::MAX_BITS - bit_page.leading_zeros() as usize);
+
+        match self.kind {
+            BitPageVecKind::SparseWithZeroesHole => self
+                .pages
+                .as_ref()
+                .and_then(|pages| pages.last())
+                .map_or(0, |page| end_of_page((page.page_idx, page.bit_page))),
+            BitPageVecKind::Runs => self.runs.as_ref().and_then(|runs| runs.last()).map_or(0, |run| run.end()),
+            BitPageVecKind::Containers => self
+                .containers
+                .as_ref()
+                .and_then(|containers| containers.last())
+                .and_then(|container| container.page_items().last())
+                .map_or(0, end_of_page),
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::bit_page_vec::BitPageVecKind;
+    use crate::bit_page_vec_resize::GrowFill;
+    use crate::BitPageVec;
+
+    #[test]
+    fn test_grow_and_truncate() {
+        let mut bit_page_vec = BitPageVec::from_ranges(vec![10..20, 100..130], (3, 0));
+        bit_page_vec.grow_to((4, 0), GrowFill::Zeroes);
+        assert_eq!(bit_page_vec.last_bit_index(), (4, 0));
+        assert_eq!(bit_page_vec.iter().collect_vec(), (10..20).chain(100..130).collect_vec());
+
+        bit_page_vec.grow_to((5, 10), GrowFill::Ones);
+        assert_eq!(bit_page_vec.active_bits_count(), 10 + 30 + 74);
+        assert!(bit_page_vec.contains(256) && bit_page_vec.contains(329) && !bit_page_vec.contains(255));
+
+        bit_page_vec.truncate((1, 50));
+        assert_eq!(bit_page_vec.iter().collect_vec(), (10..20).chain(100..114).collect_vec());
+
+        let mut all_ones = BitPageVec::all_ones((1, 0));
+        all_ones.resize((3, 0), GrowFill::Zeroes);
+        assert_eq!(all_ones.iter().collect_vec(), (0..64).collect_vec());
+
+        let mut all_ones = BitPageVec::all_ones((1, 0));
+        all_ones.resize((3, 0), GrowFill::Ones);
+        assert!(matches!(all_ones.kind(), BitPageVecKind::AllOnes));
+        assert_eq!(all_ones.active_bits_count(), 192);
+
+        // ones hole vector... bits past the old end are filled as requested
+        let mut ones_hole = BitPageVec::all_ones((100, 3));
+        ones_hole.remove(5);
+        ones_hole.resize((200, 0), GrowFill::Zeroes);
+        assert_eq!(ones_hole.active_bits_count(), 6403 - 1);
+        ones_hole.resize((50, 0), GrowFill::Zeroes);
+        assert_eq!(ones_hole.active_bits_count(), 3200 - 1);
+    }
+
+    #[test]
+    fn test_auto_grow() {
+        let mut bit_page_vec = BitPageVec::all_zeros((1, 0)).with_auto_grow(true);
+        bit_page_vec.insert(10);
+        bit_page_vec.insert(1000);
+        assert_eq!(bit_page_vec.last_bit_index(), (15, 41));
+
+        // auto grow survives changes of kind
+        bit_page_vec.set_range(0..1001);
+        bit_page_vec.insert(5000);
+        assert!(bit_page_vec.auto_grow());
+        assert_eq!(bit_page_vec.active_bits_count(), 1002);
+        assert_eq!(bit_page_vec.last_bit_index(), (78, 9));
+
+        // without auto grow the universe is fixed
+        let mut bit_page_vec = BitPageVec::all_zeros((1, 0));
+        bit_page_vec.insert(1000);
+        assert_eq!(bit_page_vec.last_bit_index(), (1, 0));
+        assert_eq!(bit_page_vec.active_bits_count(), 0);
+    }
+}
@@ -0,0 +1,169 @@
+use std::cmp::min;
+use std::iter::empty;
+
+use itertools::{EitherOrBoth, Itertools};
+
+// @author shailendra.sharma
+use crate::bit_page::{split_bit_index, BitPageWithPosition};
+use crate::bit_page_vec::BitPageVecKind;
+use crate::bit_page_vec_active_bits::BitPageVecActiveBitsIterator;
+use crate::bit_page_vec_ids::BitPageVecIdsIterator;
+use crate::bit_page_vec_iter::{PageItem, PageIterator};
+use crate::bit_page_vec_range::range_mask;
+use crate::{BitPage, BitPageVec};
+
+impl BitPageVec {
+    /// same as active_bits... but from the highest bit to the lowest
+    pub fn active_bits_rev(&self) -> BitPageVecActiveBitsIterator<'_> {
+        match self.kind {
+            BitPageVecKind::AllZeroes => BitPageVecActiveBitsIterator::None,
+            _ => {
+                let iter = self
+                    .active_pages_rev()
+                    .flat_map(|(page_idx, bit_page)| BitPage::active_bits(bit_page).rev().map(move |bit_idx| (page_idx, bit_idx)));
+
+                BitPageVecActiveBitsIterator::Some { iter: Box::new(iter) }
+            }
+        }
+    }
+
+    /// ids of all active bits (within last_bit_index) in descending order... lazy, so taking the top n ids is cheap
+    pub fn iter_rev(&self) -> BitPageVecIdsIterator<'_> {
+        match self.kind {
+            BitPageVecKind::AllZeroes => BitPageVecIdsIterator::None,
+            _ => BitPageVecIdsIterator::from_pages_rev(self.active_pages_rev()),
+        }
+    }
+
+    // non-empty pages (masked to the universe) in descending page order
+    pub(crate) fn active_pages_rev(&self) -> PageIterator<'_> {
+        let universe = self.universe();
+        let universe_pages = universe.pages();
+        let trim = move |(page_idx, bit_page): PageItem| {
+            let bit_page = bit_page & universe.page_mask(page_idx);
+
+            if BitPage::is_zeroes(&bit_page) {
+                None
+            } else {
+                Some((page_idx, bit_page))
+            }
+        };
+
+        match self.kind {
+            BitPageVecKind::AllZeroes => Box::new(empty::<PageItem>()),
+            BitPageVecKind::AllOnes => Box::new(
+                (0..universe_pages)
+                    .rev()
+                    .filter_map(move |page_idx| trim((page_idx, BitPage::ones()))),
+            ),
+            BitPageVecKind::SparseWithZeroesHole => Box::new(
+                self.pages
+                    .iter()
+                    .flat_map(|pages| pages.iter().rev())
+                    .map(|BitPageWithPosition { page_idx, bit_page }| (*page_idx, *bit_page))
+                    .skip_while(move |(page_idx, _)| *page_idx >= universe_pages)
+                    .filter_map(trim),
+            ),
+            BitPageVecKind::SparseWithOnesHole => Box::new(
+                (0..universe_pages)
+                    .rev()
+                    .merge_join_by(
+                        self.pages
+                            .iter()
+                            .flat_map(|pages| pages.iter().rev())
+                            .skip_while(move |page| page.page_idx >= universe_pages),
+                        |page_1_idx, page_2| page_2.page_idx.cmp(page_1_idx),
+                    )
+                    .filter_map(move |either| match either {
+                        EitherOrBoth::Both(_, BitPageWithPosition { page_idx, bit_page }) => trim((*page_idx, *bit_page)),
+                        EitherOrBoth::Left(page_idx) => trim((page_idx, BitPage::ones())),
+                        EitherOrBoth::Right(_) => None,
+                    }),
+            ),
+            BitPageVecKind::Runs => {
+                let universe_len = universe.len();
+                let iter = self
+                    .runs
+                    .iter()
+                    .flat_map(|runs| runs.iter().rev())
+                    .filter(move |run| run.start() < min(run.end(), universe_len))
+                    .flat_map(move |run| {
+                        let start = split_bit_index(run.start());
+                        let end = split_bit_index(min(run.end(), universe_len) - 1);
+
+                        (start.0..=end.0)
+                            .rev()
+                            .map(move |page_idx| (page_idx, range_mask(page_idx, start, end)))
+                    })
+                    // adjacent runs may share a page
+                    .coalesce(|(page_1_idx, page_one), (page_2_idx, page_two)| {
+                        if page_1_idx == page_2_idx {
+                            Ok((page_1_idx, page_one | page_two))
+                        } else {
+                            Err(((page_1_idx, page_one), (page_2_idx, page_two)))
+                        }
+                    });
+
+                Box::new(iter)
+            }
+            BitPageVecKind::Containers => Box::new(
+                self.containers
+                    .iter()
+                    .flat_map(|containers| containers.iter().rev())
+                    // a container holds at most CONTAINER_PAGES pages
+                    .flat_map(|container| container.page_items().collect_vec().into_iter().rev())
+                    .skip_while(move |(page_idx, _)| *page_idx >= universe_pages)
+                    .filter_map(trim),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::bit_page_vec_containers::pages_to_containers;
+    use crate::bit_page_vec_fixtures::vectors;
+    use crate::bit_page_vec_runs::BitRun;
+    use crate::BitPageVec;
+
+    #[test]
+    fn test_iter_rev() {
+        let universe = (12, 17);
+
+        let mut ones_hole = BitPageVec::all_ones(universe);
+        ones_hole.clear_range(63..65);
+        ones_hole.clear_range(300..450);
+
+        let runs = [(0, 1), (64, 536), (600, 10), (700, 200)]
+            .iter()
+            .map(|(start, len)| BitRun { start: *start, len: *len })
+            .collect();
+
+        let sparse = BitPageVec::from_ranges(vec![5..6, 63..64, 128..200, 700..720], universe);
+        let containers = pages_to_containers(sparse.pages.clone().unwrap());
+
+        // every kind... plus gaps and runs at page boundaries (and a run past the universe)
+        let mut vectors = vectors(universe);
+        vectors.extend(vec![
+            ones_hole,
+            BitPageVec::from_runs(runs, universe),
+            BitPageVec::from_containers(containers, universe),
+            sparse,
+        ]);
+
+        for bit_page_vec in vectors.iter() {
+            let mut expected = bit_page_vec.iter().collect_vec();
+            expected.reverse();
+            assert_eq!(bit_page_vec.iter_rev().collect_vec(), expected, "{:?}", bit_page_vec);
+
+            let mut expected = bit_page_vec.active_bits().collect_vec();
+            expected.reverse();
+            assert_eq!(bit_page_vec.active_bits_rev().collect_vec(), expected, "{:?}", bit_page_vec);
+        }
+
+        let ids = (0..10_000u64).map(|id| id * 7).collect::<BitPageVec>();
+        assert_eq!(ids.iter_rev().take(3).collect_vec(), vec![69_993, 69_986, 69_979]);
+    }
+}
@@ -0,0 +1,351 @@
+use std::cmp::{max, min};
+use std::fmt;
+use std::ops::Range;
+
+use itertools::Itertools;
+
+// @author shailendra.sharma
+use crate::bit_page::{join_bit_index, split_bit_index, BitPageWithPosition};
+use crate::bit_page_vec::BitPageVecKind;
+use crate::bit_page_vec_iter::PageItem;
+use crate::bit_page_vec_range::{range_mask, RangeOp};
+use crate::{BitPageVec, Universe, UniversePolicy};
+
+/// contiguous run of active bits [start, start + len)
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct BitRun {
+    pub(crate) start: u64,
+    pub(crate) len: u64,
+}
+
+impl BitRun {
+    #[inline]
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    #[inline]
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // exclusive end of the run
+    #[inline]
+    pub fn end(&self) -> u64 {
+        self.start + self.len
+    }
+
+    #[inline]
+    fn from_range(range: Range<u64>) -> BitRun {
+        BitRun {
+            start: range.start,
+            len: range.end - range.start,
+        }
+    }
+}
+
+impl fmt::Debug for BitRun {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.start, self.end())
+    }
+}
+
+impl BitPageVec {
+    pub(crate) fn from_runs<U: Into<Universe>>(runs: Vec<BitRun>, universe: U) -> BitPageVec {
+        let last_bit_index = universe.into().last_bit_index();
+
+        if runs.is_empty() {
+            BitPageVec::all_zeros(last_bit_index)
+        } else {
+            BitPageVec {
+                kind: BitPageVecKind::Runs,
+                pages: None,
+                runs: Some(runs),
+                containers: None,
+                last_bit_index,
+                auto_grow: false,
+                compaction_policy: None,
+                universe_policy: UniversePolicy::default(),
+            }
+        }
+    }
+
+    pub(crate) fn runs_set_bit(&mut self, index: u64) {
+        if let Some(ref mut runs) = self.runs {
+            // position of first run starting after index
+            let position = runs.partition_point(|run| run.start <= index);
+
+            let extends_previous = position > 0 && runs[position - 1].end() >= index;
+            let extends_next = position < runs.len() && runs[position].start == index + 1;
+
+            match (extends_previous, extends_next) {
+                (true, _) if runs[position - 1].end() > index => {
+                    // already set
+                }
+                (true, true) => {
+                    // index fills the gap between two runs
+                    let next = runs.remove(position);
+                    runs[position - 1].len += 1 + next.len;
+                }
+                (true, false) => runs[position - 1].len += 1,
+                (false, true) => {
+                    runs[position].start -= 1;
+                    runs[position].len += 1;
+                }
+                (false, false) => runs.insert(position, BitRun { start: index, len: 1 }),
+            }
+        }
+    }
+
+    pub(crate) fn runs_clear_bit(&mut self, index: u64) {
+        if let Some(ref mut runs) = self.runs {
+            let position = runs.partition_point(|run| run.start <= index);
+            if position == 0 || runs[position - 1].end() <= index {
+                // not set
+                return;
+            }
+
+            let run = runs[position - 1];
+            let head = BitRun::from_range(run.start..index);
+            let tail = BitRun::from_range(index + 1..run.end());
+
+            match (head.is_empty(), tail.is_empty()) {
+                (true, true) => {
+                    runs.remove(position - 1);
+                }
+                (true, false) => runs[position - 1] = tail,
+                (false, true) => runs[position - 1] = head,
+                (false, false) => {
+                    runs[position - 1] = head;
+                    runs.insert(position, tail);
+                }
+            }
+
+            if runs.is_empty() {
+                self.assign(BitPageVec::all_zeros(self.last_bit_index));
+            }
+        }
+    }
+
+    pub(crate) fn runs_is_bit_set(&self, index: u64) -> bool {
+        if let Some(ref runs) = self.runs {
+            let position = runs.partition_point(|run| run.start <= index);
+            position > 0 && runs[position - 1].end() > index
+        } else {
+            false
+        }
+    }
+}
+
+// pages of a zeroes hole vector holding the runs... runs must be sorted and non-overlapping
+pub(crate) fn run_pages<'a, I>(runs: I) -> impl Iterator<Item = PageItem> + 'a
+where
+    I: Iterator<Item = BitRun> + 'a,
+{
+    runs.filter(|run| !run.is_empty())
+        .flat_map(|run| {
+            let start = split_bit_index(run.start);
+            let end = split_bit_index(run.end() - 1);
+
+            (start.0..=end.0).map(move |page_idx| (page_idx, range_mask(page_idx, start, end)))
+        })
+        .coalesce(|(page_1_idx, page_one), (page_2_idx, page_two)| {
+            if page_1_idx == page_2_idx {
+                Ok((page_1_idx, page_one | page_two))
+            } else {
+                Err(((page_1_idx, page_one), (page_2_idx, page_two)))
+            }
+        })
+}
+
+// runs of active bits in the pages of a zeroes hole vector
+pub(crate) fn pages_to_runs(pages: &[BitPageWithPosition]) -> Vec<BitRun> {
+    let mut runs: Vec<BitRun> = Vec::new();
+
+    for BitPageWithPosition { page_idx, bit_page } in pages {
+        let mut bit_page = *bit_page;
+        let mut bit_idx = 0;
+
+        while bit_page != 0 {
+            let zeroes = bit_page.trailing_zeros() as usize;
+            bit_page >>= zeroes;
+            bit_idx += zeroes;
+
+            let ones = bit_page.trailing_ones() as usize;
+            bit_page = bit_page.checked_shr(ones as u32).unwrap_or(0);
+
+            let start = join_bit_index(*page_idx, bit_idx);
+            match runs.last_mut() {
+                Some(run) if run.end() == start => run.len += ones as u64,
+                _ => runs.push(BitRun { start, len: ones as u64 }),
+            }
+
+            bit_idx += ones;
+        }
+    }
+
+    runs
+}
+
+// number of runs pages_to_runs would produce... without building them
+pub(crate) fn count_runs(pages: &[BitPageWithPosition]) -> usize {
+    let mut count = 0;
+    let mut previous: Option<&BitPageWithPosition> = None;
+
+    for page in pages {
+        // a run starts at every active bit whose lower neighbour is inactive
+        count += (page.bit_page & !(page.bit_page << 1)).count_ones() as usize;
+
+        if let Some(previous) = previous {
+            if previous.page_idx + 1 == page.page_idx && previous.bit_page >> 63 == 1 && page.bit_page & 1 == 1 {
+                // run continues from previous page
+                count -= 1;
+            }
+        }
+
+        previous = Some(page);
+    }
+
+    count
+}
+
+// applies op over a non-empty range to sorted non-overlapping runs
+pub(crate) fn apply_range_op_runs(runs: Vec<BitRun>, range: Range<u64>, op: RangeOp) -> Vec<BitRun> {
+    let mut result = Vec::with_capacity(runs.len() + 2);
+    let mut runs = runs.into_iter().peekable();
+
+    // runs ending before the range are kept as is
+    while let Some(run) = runs.next_if(|run| run.end() <= range.start) {
+        result.push(run);
+    }
+
+    // runs overlapping the range... parts outside the range are kept
+    let mut inner = Vec::new();
+    let mut tail = None;
+    while let Some(run) = runs.next_if(|run| run.start < range.end) {
+        if run.start < range.start {
+            result.push(BitRun::from_range(run.start..range.start));
+        }
+
+        if run.end() > range.end {
+            tail = Some(BitRun::from_range(range.end..run.end()));
+        }
+
+        inner.push(max(run.start, range.start)..min(run.end(), range.end));
+    }
+
+    match op {
+        RangeOp::Set => result.push(BitRun::from_range(range)),
+        RangeOp::Clear => {}
+        RangeOp::Flip => {
+            // gaps between the overlapping runs within the range
+            let mut start = range.start;
+            for inner_range in inner {
+                if start < inner_range.start {
+                    result.push(BitRun::from_range(start..inner_range.start));
+                }
+                start = inner_range.end;
+            }
+
+            if start < range.end {
+                result.push(BitRun::from_range(start..range.end));
+            }
+        }
+    }
+
+    result.extend(tail);
+    result.extend(runs);
+
+    // merge runs touching each other at the range boundaries
+    result
+        .into_iter()
+        .filter(|run| !run.is_empty())
+        .coalesce(|first, second| {
+            if first.end() >= second.start {
+                Ok(BitRun::from_range(first.start..max(first.end(), second.end())))
+            } else {
+                Err((first, second))
+            }
+        })
+        .collect_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::bit_page_vec::BitPageVecKind;
+    use crate::bit_page_vec_iter::BitPageVecIter;
+    use crate::bit_page_vec_runs::{count_runs, pages_to_runs, run_pages, BitRun};
+    use crate::BitPageVec;
+
+    fn runs_vec(ranges: &[(u64, u64)], last_bit_index: (usize, usize)) -> BitPageVec {
+        let runs = ranges.iter().map(|(start, end)| BitRun::from_range(*start..*end)).collect_vec();
+        BitPageVec::from_runs(runs, last_bit_index)
+    }
+
+    #[test]
+    fn test_runs_pages_round_trip() {
+        let bit_page_vec = BitPageVec::from_ranges(vec![3..5, 60..200, 256..257, 1000..1100], (20, 0));
+        let pages = bit_page_vec.pages.clone().unwrap();
+
+        let runs = pages_to_runs(&pages);
+        assert_eq!(runs.len(), count_runs(&pages));
+        assert_eq!(format!("{:?}", runs), "[3..5, 60..200, 256..257, 1000..1100]");
+
+        let round_trip = run_pages(runs.into_iter()).collect_vec();
+        assert_eq!(round_trip, pages.iter().map(|page| (page.page_idx, page.bit_page)).collect_vec());
+    }
+
+    #[test]
+    fn test_runs_set_clear() {
+        let mut bit_page_vec = runs_vec(&[(10, 20), (22, 30)], (10, 0));
+
+        bit_page_vec.insert(21);
+        bit_page_vec.insert(20);
+        bit_page_vec.insert(9);
+        bit_page_vec.insert(31);
+        assert_eq!(format!("{:?}", bit_page_vec.runs.as_ref().unwrap()), "[9..30, 31..32]");
+
+        bit_page_vec.remove(15);
+        bit_page_vec.remove(31);
+        assert_eq!(format!("{:?}", bit_page_vec.runs.as_ref().unwrap()), "[9..15, 16..30]");
+        assert!(bit_page_vec.contains(9));
+        assert!(!bit_page_vec.contains(15));
+        assert_eq!(bit_page_vec.active_bits_count(), 20);
+        assert_eq!(bit_page_vec.rank(17), 7);
+        assert_eq!(bit_page_vec.select(7), Some(17));
+
+        bit_page_vec.flip_range(0..20);
+        assert_eq!(bit_page_vec.iter().collect_vec(), (0..9).chain(15..16).chain(20..30).collect_vec());
+    }
+
+    #[test]
+    fn test_runs_boolean_ops() {
+        let first = runs_vec(&[(0, 1000), (5000, 9000)], (200, 0));
+        let second = runs_vec(&[(500, 6000)], (200, 0));
+
+        let and = BitPageVecIter::and(first.page_iter(), second.page_iter()).into_bit_page_vec();
+        assert_eq!(and.iter().collect_vec(), (500..1000).chain(5000..6000).collect_vec());
+
+        let or = BitPageVecIter::or(first.page_iter(), second.page_iter()).into_bit_page_vec();
+        assert_eq!(or.iter().collect_vec(), (0..9000).collect_vec());
+
+        let not = first.page_iter().not().into_bit_page_vec();
+        assert_eq!(not.iter().collect_vec(), (1000..5000).chain(9000..12800).collect_vec());
+    }
+
+    #[test]
+    fn test_compaction_chooses_runs() {
+        let bit_page_vec = BitPageVec::from_ranges(vec![0..64_000, 100_000..164_000], (10_000, 0));
+        let compacted = bit_page_vec.page_iter().into_bit_page_vec();
+
+        assert!(matches!(compacted.kind(), BitPageVecKind::Runs));
+        assert_eq!(compacted.active_bits_count(), 128_000);
+    }
+}
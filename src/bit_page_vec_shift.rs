@@ -0,0 +1,207 @@
+use arrayvec::ArrayVec;
+use itertools::Itertools;
+
+// @author shailendra.sharma
+use crate::bit_page::split_bit_index;
+use crate::bit_page_vec::BitPageVecKind;
+use crate::bit_page_vec_iter::{BitPageVecIter, PageItem};
+use crate::{BitPage, BitPageVec, Universe};
+
+impl BitPageVec {
+    /// moves every active bit n positions up (id -> id + n)... universe grows by n and the first n bits are cleared
+    pub fn shift_left(&mut self, n: u64) {
+        if n == 0 {
+            return;
+        }
+
+        let universe_len = self.universe_len().checked_add(n).expect("shift_left past the largest universe");
+        let universe = Universe::new(universe_len);
+
+        let shifted = match self.kind {
+            BitPageVecKind::AllZeroes => BitPageVec::all_zeros(universe),
+            BitPageVecKind::AllOnes => {
+                let mut shifted = BitPageVec::all_ones(universe);
+                shifted.clear_range(0..n);
+                shifted
+            }
+            BitPageVecKind::SparseWithZeroesHole | BitPageVecKind::Runs | BitPageVecKind::Containers => {
+                let iter = shift_pages_up(self.page_iter().into_active_pages(), n);
+                BitPageVecIter::new(BitPageVecKind::SparseWithZeroesHole, Box::new(iter), universe).into_bit_page_vec()
+            }
+            BitPageVecKind::SparseWithOnesHole => {
+                // shift the (sparse) complement... and flip it back so the ones hole is kept
+                let iter = shift_pages_up(self.page_iter().not().into_active_pages(), n);
+                let mut shifted = BitPageVecIter::new(BitPageVecKind::SparseWithZeroesHole, Box::new(iter), universe)
+                    .not()
+                    .into_bit_page_vec();
+                shifted.clear_range(0..n);
+                shifted
+            }
+        };
+
+        self.assign(shifted);
+    }
+
+    /// moves every active bit n positions down (id -> id - n)... first n bits are dropped and universe shrinks by n
+    pub fn shift_right(&mut self, n: u64) {
+        if n == 0 {
+            return;
+        }
+
+        let universe = Universe::new(self.universe_len().saturating_sub(n));
+
+        let shifted = match self.kind {
+            BitPageVecKind::AllZeroes => BitPageVec::all_zeros(universe),
+            BitPageVecKind::AllOnes => BitPageVec::all_ones(universe),
+            BitPageVecKind::SparseWithZeroesHole | BitPageVecKind::Runs | BitPageVecKind::Containers => {
+                let iter = shift_pages_down(self.page_iter().into_active_pages(), n);
+                BitPageVecIter::new(BitPageVecKind::SparseWithZeroesHole, Box::new(iter), universe).into_bit_page_vec()
+            }
+            BitPageVecKind::SparseWithOnesHole => {
+                // shift the (sparse) complement... and flip it back so the ones hole is kept
+                let iter = shift_pages_down(self.page_iter().not().into_active_pages(), n);
+                BitPageVecIter::new(BitPageVecKind::SparseWithZeroesHole, Box::new(iter), universe)
+                    .not()
+                    .into_bit_page_vec()
+            }
+        };
+
+        self.assign(shifted);
+    }
+
+    /// shift_left for a positive delta and shift_right for a negative one
+    pub fn offset_by(&mut self, delta: i64) {
+        if delta >= 0 {
+            self.shift_left(delta as u64);
+        } else {
+            self.shift_right(delta.unsigned_abs());
+        }
+    }
+}
+
+// zeroes hole pages moved n bits up... bits are carried into the next page
+pub(crate) fn shift_pages_up<'a, I>(iter: I, n: u64) -> impl Iterator<Item = PageItem> + 'a
+where
+    I: Iterator<Item = PageItem> + 'a,
+{
+    let (page_shift, bit_shift) = split_bit_index(n);
+
+    iter.flat_map(move |(page_idx, bit_page)| {
+        // pages moved past the largest page index are past any universe... so they are dropped
+        let mut pages = ArrayVec::<[PageItem; 2]>::new();
+        if let Some(high_idx) = page_idx.checked_add(page_shift) {
+            if bit_shift == 0 {
+                pages.push((high_idx, bit_page));
+            } else {
+                pages.push((high_idx, bit_page << bit_shift));
+                if let Some(carry_idx) = high_idx.checked_add(1) {
+                    pages.push((carry_idx, bit_page >> (BitPage::MAX_BITS - bit_shift)));
+                }
+            }
+        }
+
+        pages
+    })
+    .coalesce(merge_same_page)
+    .filter(|(_, bit_page)| !BitPage::is_zeroes(bit_page))
+}
+
+// zeroes hole pages moved n bits down... bits are carried into the previous page and bits below zero are dropped
+pub(crate) fn shift_pages_down<'a, I>(iter: I, n: u64) -> impl Iterator<Item = PageItem> + 'a
+where
+    I: Iterator<Item = PageItem> + 'a,
+{
+    let (page_shift, bit_shift) = split_bit_index(n);
+
+    iter.flat_map(move |(page_idx, bit_page)| {
+        let mut pages = ArrayVec::<[PageItem; 2]>::new();
+        if bit_shift > 0 {
+            if let Some(low_idx) = page_idx.checked_sub(page_shift + 1) {
+                pages.push((low_idx, bit_page << (BitPage::MAX_BITS - bit_shift)));
+            }
+        }
+        if let Some(high_idx) = page_idx.checked_sub(page_shift) {
+            pages.push((high_idx, bit_page >> bit_shift));
+        }
+
+        pages
+    })
+    .coalesce(merge_same_page)
+    .filter(|(_, bit_page)| !BitPage::is_zeroes(bit_page))
+}
+
+#[inline]
+fn merge_same_page((page_1_idx, page_one): PageItem, (page_2_idx, page_two): PageItem) -> Result<PageItem, (PageItem, PageItem)> {
+    if page_1_idx == page_2_idx {
+        Ok((page_1_idx, page_one | page_two))
+    } else {
+        Err(((page_1_idx, page_one), (page_2_idx, page_two)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::bit_page_vec::BitPageVecKind;
+    use crate::bit_page_vec_fixtures::vectors;
+    use crate::bit_page_vec_shift::shift_pages_up;
+    use crate::BitPageVec;
+
+    #[test]
+    fn test_shift() {
+        for bit_page_vec in vectors((20, 7)) {
+            let ids = bit_page_vec.iter().collect_vec();
+            let universe_len = bit_page_vec.universe().len();
+
+            for n in [1, 5, 63, 64, 65, 130] {
+                let mut shifted = bit_page_vec.clone();
+                shifted.shift_left(n);
+                assert_eq!(shifted.universe().len(), universe_len + n);
+                assert_eq!(
+                    shifted.iter().collect_vec(),
+                    ids.iter().map(|id| id + n).collect_vec(),
+                    "{:?} << {}",
+                    bit_page_vec,
+                    n
+                );
+
+                let mut shifted = bit_page_vec.clone();
+                shifted.offset_by(-(n as i64));
+                assert_eq!(shifted.universe().len(), universe_len - n);
+                assert_eq!(
+                    shifted.iter().collect_vec(),
+                    ids.iter().filter(|id| **id >= n).map(|id| id - n).collect_vec(),
+                    "{:?} >> {}",
+                    bit_page_vec,
+                    n
+                );
+            }
+        }
+
+        let mut ones_hole = vectors((20, 7)).pop().unwrap();
+        ones_hole.shift_left(70);
+        assert!(matches!(ones_hole.kind(), BitPageVecKind::SparseWithOnesHole));
+    }
+
+    #[test]
+    fn test_shift_pages_up_near_the_largest_page() {
+        let pages = vec![(0, 0b11), (usize::MAX - 1, 1 << 63), (usize::MAX, 1)];
+
+        assert_eq!(
+            shift_pages_up(pages.clone().into_iter(), 1).collect_vec(),
+            vec![(0, 0b110), (usize::MAX, 0b11)]
+        );
+        assert_eq!(
+            shift_pages_up(pages.into_iter(), 64).collect_vec(),
+            vec![(1, 0b11), (usize::MAX, 1 << 63)]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "shift_left past the largest universe")]
+    fn test_shift_left_past_the_largest_universe() {
+        let mut bit_page_vec = BitPageVec::all_ones((1, 0));
+        bit_page_vec.shift_left(u64::MAX);
+    }
+}
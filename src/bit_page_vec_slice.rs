@@ -0,0 +1,184 @@
+use std::cmp::{max, min};
+use std::iter::empty;
+use std::ops::Range;
+
+use itertools::{EitherOrBoth, Itertools};
+
+// @author shailendra.sharma
+use crate::bit_page::{split_bit_index, BitPageWithPosition};
+use crate::bit_page_vec::BitPageVecKind;
+use crate::bit_page_vec_containers::CONTAINER_PAGES;
+use crate::bit_page_vec_ids::BitPageVecIdsIterator;
+use crate::bit_page_vec_iter::{BitPageVecIter, PageItem, PageIterator};
+use crate::bit_page_vec_range::range_mask;
+use crate::bit_page_vec_runs::{run_pages, BitRun};
+use crate::bit_page_vec_shift::shift_pages_down;
+use crate::{BitPage, BitPageVec};
+
+/// borrowed view of the bits of a BitPageVec within a range... ids keep their position in the vector
+pub struct BitPageVecSlice<'a> {
+    bit_page_vec: &'a BitPageVec,
+    range: Range<u64>,
+}
+
+impl<'a> BitPageVecSlice<'a> {
+    pub fn range(&self) -> Range<u64> {
+        self.range.clone()
+    }
+
+    /// zeroes hole stream of the active pages within the range... boundary pages are masked
+    pub fn page_iter(&self) -> BitPageVecIter<'a> {
+        BitPageVecIter::new(BitPageVecKind::SparseWithZeroesHole, self.active_pages(), self.range.end)
+    }
+
+    /// ids of all active bits within the range in ascending order
+    pub fn iter(&self) -> BitPageVecIdsIterator<'a> {
+        BitPageVecIdsIterator::from_pages(self.active_pages())
+    }
+
+    pub fn active_bits_count(&self) -> usize {
+        self.active_pages().map(|(_, bit_page)| bit_page.count_ones() as usize).sum()
+    }
+
+    /// copy of the slice re-based to zero... i.e. range.start becomes bit 0 and the universe is the range length
+    pub fn to_bit_page_vec(&self) -> BitPageVec {
+        let iter = shift_pages_down(self.active_pages(), self.range.start);
+
+        BitPageVecIter::new(
+            BitPageVecKind::SparseWithZeroesHole,
+            Box::new(iter),
+            self.range.end - self.range.start,
+        )
+        .into_bit_page_vec()
+    }
+
+    fn active_pages(&self) -> PageIterator<'a> {
+        if self.range.start >= self.range.end {
+            return Box::new(empty::<PageItem>());
+        }
+
+        let start = split_bit_index(self.range.start);
+        let end = split_bit_index(self.range.end - 1);
+        let (start_page, end_page) = (start.0, end.0);
+
+        let bit_page_vec = self.bit_page_vec;
+        let pages: PageIterator<'a> = match bit_page_vec.kind {
+            BitPageVecKind::AllZeroes => Box::new(empty::<PageItem>()),
+            BitPageVecKind::AllOnes => Box::new((start_page..=end_page).map(|page_idx| (page_idx, BitPage::ones()))),
+            BitPageVecKind::SparseWithZeroesHole => Box::new(
+                stored_pages(bit_page_vec, start_page)
+                    .iter()
+                    .map(|BitPageWithPosition { page_idx, bit_page }| (*page_idx, *bit_page)),
+            ),
+            BitPageVecKind::SparseWithOnesHole => Box::new(
+                (start_page..=end_page)
+                    .merge_join_by(stored_pages(bit_page_vec, start_page).iter(), |page_1_idx, page_2| {
+                        page_1_idx.cmp(&page_2.page_idx)
+                    })
+                    .filter_map(|either| match either {
+                        EitherOrBoth::Both(_, BitPageWithPosition { page_idx, bit_page }) => Some((*page_idx, *bit_page)),
+                        EitherOrBoth::Left(page_idx) => Some((page_idx, BitPage::ones())),
+                        EitherOrBoth::Right(_) => None,
+                    }),
+            ),
+            BitPageVecKind::Runs => {
+                let runs = bit_page_vec.runs.as_deref().unwrap_or(&[]);
+                let (range_start, range_end) = (self.range.start, self.range.end);
+
+                // runs overlapping the range... clipped to it
+                let position = runs.partition_point(|run| run.end() <= range_start);
+                let iter = runs[position..]
+                    .iter()
+                    .take_while(move |run| run.start() < range_end)
+                    .map(move |run| {
+                        let start = max(run.start(), range_start);
+                        BitRun {
+                            start,
+                            len: min(run.end(), range_end) - start,
+                        }
+                    });
+
+                Box::new(run_pages(iter))
+            }
+            BitPageVecKind::Containers => {
+                let containers = bit_page_vec.containers.as_deref().unwrap_or(&[]);
+                let position = containers.partition_point(|container| container.region_idx() < start_page / CONTAINER_PAGES);
+
+                Box::new(
+                    containers[position..]
+                        .iter()
+                        .flat_map(|container| container.page_items())
+                        .skip_while(move |(page_idx, _)| *page_idx < start_page),
+                )
+            }
+        };
+
+        // boundary pages are masked... and pages past the universe are dropped too
+        let universe = bit_page_vec.universe();
+        let iter = pages
+            .take_while(move |(page_idx, _)| *page_idx <= end_page)
+            .filter_map(move |(page_idx, bit_page)| {
+                let bit_page = bit_page & range_mask(page_idx, start, end) & universe.page_mask(page_idx);
+                if BitPage::is_zeroes(&bit_page) {
+                    None
+                } else {
+                    Some((page_idx, bit_page))
+                }
+            });
+
+        Box::new(iter)
+    }
+}
+
+impl BitPageVec {
+    /// borrowed view of the bits within range (clipped to the universe)
+    pub fn slice_view(&self, range: Range<u64>) -> BitPageVecSlice<'_> {
+        BitPageVecSlice {
+            bit_page_vec: self,
+            range: self.clip_range(range),
+        }
+    }
+
+    /// bits within range (clipped to the universe) as a new vector re-based to zero
+    pub fn slice(&self, range: Range<u64>) -> BitPageVec {
+        self.slice_view(range).to_bit_page_vec()
+    }
+}
+
+// stored pages starting from the first one at or after start_page
+#[inline]
+fn stored_pages(bit_page_vec: &BitPageVec, start_page: usize) -> &[BitPageWithPosition] {
+    let pages = bit_page_vec.pages.as_deref().unwrap_or(&[]);
+    let position = pages.partition_point(|page| page.page_idx < start_page);
+
+    &pages[position..]
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::bit_page_vec_fixtures::vectors;
+    use crate::BitPageVecSlice;
+
+    #[test]
+    fn test_slice() {
+        for bit_page_vec in vectors((40, 0)).iter() {
+            let ids = bit_page_vec.iter().collect_vec();
+
+            for range in [0..0, 0..64, 1..2, 63..129, 150..1501, 640..10_000] {
+                let expected = ids.iter().copied().filter(|id| range.contains(id)).collect_vec();
+
+                let view: BitPageVecSlice<'_> = bit_page_vec.slice_view(range.clone());
+                assert_eq!(view.iter().collect_vec(), expected, "{:?} {:?}", bit_page_vec, range);
+                assert_eq!(view.active_bits_count(), expected.len());
+                assert_eq!(view.page_iter().into_bit_page_vec().iter().collect_vec(), expected);
+
+                let slice = bit_page_vec.slice(range.clone());
+                let start = view.range().start;
+                assert_eq!(slice.universe().len(), view.range().end - start);
+                assert_eq!(slice.iter().collect_vec(), expected.iter().map(|id| id - start).collect_vec());
+            }
+        }
+    }
+}
@@ -0,0 +1,306 @@
+use std::iter::FromIterator;
+use std::mem::size_of;
+
+// @author shailendra.sharma
+use crate::bit_page::join_bit_index;
+use crate::bit_page_vec::BitPageVecKind;
+use crate::bit_page_vec_iter::PageItem;
+use crate::{BitPage, BitPageVec};
+
+/// memory, shape and density of one vector
+#[derive(Clone, Debug, PartialEq)]
+pub struct BitPageVecStats {
+    pub kind: BitPageVecKind,
+    // ids the vector can hold... None for a DbBitPageVec which has no universe
+    pub universe_len: Option<u64>,
+    // stored pages, runs or containers (depending on the kind)
+    pub stored: usize,
+    pub active_bits: u64,
+    // see estimated_bytes
+    pub heap_bytes: usize,
+    // bytes DbBitPageVec::encode writes for the active pages
+    pub encoded_bytes: usize,
+    // pages of the universe (or upto the last stored page) by their active bits
+    pub full_pages: usize,
+    pub empty_pages: usize,
+    pub mixed_pages: usize,
+    // runs of consecutive active bits
+    pub runs: usize,
+    // bits per density bucket... 0 when there is no histogram
+    pub bucket_bits: u64,
+    // active bits of every bucket_bits wide bucket of ids
+    pub density: Option<Vec<u64>>,
+}
+
+impl BitPageVecStats {
+    /// share of the universe which is active... None without a universe
+    pub fn fill_ratio(&self) -> Option<f64> {
+        self.universe_len.map(|universe_len| {
+            if universe_len == 0 {
+                0.0
+            } else {
+                self.active_bits as f64 / universe_len as f64
+            }
+        })
+    }
+}
+
+/// sum of the stats of many vectors... e.g. every bitmap of an index
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BitPageVecStatsAggregate {
+    pub vectors: usize,
+    // vectors of every kind (in the order the kinds were first seen)
+    pub kinds: Vec<(BitPageVecKind, usize)>,
+    pub universe_len: u64,
+    pub stored: usize,
+    pub active_bits: u64,
+    pub heap_bytes: usize,
+    pub encoded_bytes: usize,
+    pub full_pages: usize,
+    pub empty_pages: usize,
+    pub mixed_pages: usize,
+    pub runs: usize,
+    // element wise sum of the histograms with the same bucket_bits... a histogram of other bucket_bits is skipped
+    pub bucket_bits: u64,
+    pub density: Option<Vec<u64>>,
+}
+
+impl BitPageVecStatsAggregate {
+    pub fn new() -> BitPageVecStatsAggregate {
+        BitPageVecStatsAggregate::default()
+    }
+
+    pub fn add(&mut self, stats: &BitPageVecStats) {
+        self.vectors += 1;
+        match self.kinds.iter_mut().find(|(kind, _)| *kind == stats.kind) {
+            Some((_, count)) => *count += 1,
+            None => self.kinds.push((stats.kind, 1)),
+        }
+
+        self.universe_len += stats.universe_len.unwrap_or(0);
+        self.stored += stats.stored;
+        self.active_bits += stats.active_bits;
+        self.heap_bytes += stats.heap_bytes;
+        self.encoded_bytes += stats.encoded_bytes;
+        self.full_pages += stats.full_pages;
+        self.empty_pages += stats.empty_pages;
+        self.mixed_pages += stats.mixed_pages;
+        self.runs += stats.runs;
+
+        if let Some(ref density) = stats.density {
+            if self.density.is_none() {
+                self.bucket_bits = stats.bucket_bits;
+                self.density = Some(Vec::new());
+            }
+
+            let bucket_bits = self.bucket_bits;
+            if let Some(total) = self.density.as_mut().filter(|_| bucket_bits == stats.bucket_bits) {
+                if total.len() < density.len() {
+                    total.resize(density.len(), 0);
+                }
+
+                total.iter_mut().zip(density.iter()).for_each(|(total, bits)| *total += bits);
+            }
+        }
+    }
+
+    /// vectors of the kind
+    pub fn count_of(&self, kind: BitPageVecKind) -> usize {
+        self.kinds.iter().find(|(k, _)| *k == kind).map_or(0, |(_, count)| *count)
+    }
+}
+
+impl<'a> Extend<&'a BitPageVecStats> for BitPageVecStatsAggregate {
+    fn extend<T: IntoIterator<Item = &'a BitPageVecStats>>(&mut self, iter: T) {
+        for stats in iter {
+            self.add(stats);
+        }
+    }
+}
+
+impl<'a> FromIterator<&'a BitPageVecStats> for BitPageVecStatsAggregate {
+    fn from_iter<T: IntoIterator<Item = &'a BitPageVecStats>>(iter: T) -> Self {
+        let mut aggregate = BitPageVecStatsAggregate::new();
+        aggregate.extend(iter);
+        aggregate
+    }
+}
+
+impl BitPageVec {
+    pub fn stats(&self) -> BitPageVecStats {
+        self.stats_with_density(0)
+    }
+
+    /// stats along with a histogram of active bits per bucket_bits wide bucket of ids (no histogram for 0)
+    pub fn stats_with_density(&self, bucket_bits: u64) -> BitPageVecStats {
+        let universe = self.universe();
+        let mut shape = PageShape::new(bucket_bits, universe.len());
+
+        for (page_idx, bit_page) in self.page_iter().into_active_pages() {
+            shape.add(page_idx, bit_page, universe.page_mask(page_idx));
+        }
+
+        shape.into_stats(
+            self.kind,
+            Some(universe.len()),
+            self.size(),
+            self.estimated_bytes(),
+            universe.pages(),
+        )
+    }
+}
+
+// shape of the (universe masked, ascending) active pages of a vector
+pub(crate) struct PageShape {
+    active_bits: u64,
+    encoded_bytes: usize,
+    active_pages: usize,
+    full_pages: usize,
+    runs: usize,
+    // last active page... for runs continuing across pages
+    last: Option<PageItem>,
+    bucket_bits: u64,
+    density: Option<Vec<u64>>,
+}
+
+impl PageShape {
+    // universe_len sizes the histogram upfront... 0 grows it on demand
+    pub(crate) fn new(bucket_bits: u64, universe_len: u64) -> PageShape {
+        let density = universe_len
+            .checked_div(bucket_bits)
+            .map(|_| vec![0; universe_len.div_ceil(bucket_bits) as usize]);
+
+        PageShape {
+            active_bits: 0,
+            // type of the encoding
+            encoded_bytes: size_of::<u8>(),
+            active_pages: 0,
+            full_pages: 0,
+            runs: 0,
+            last: None,
+            bucket_bits,
+            density,
+        }
+    }
+
+    // mask is the page of a full vector (i.e. the universe tail is partial)
+    pub(crate) fn add(&mut self, page_idx: usize, bit_page: u64, mask: u64) {
+        if BitPage::is_zeroes(&bit_page) {
+            return;
+        }
+
+        let ones = bit_page.count_ones();
+        self.active_bits += ones as u64;
+        self.active_pages += 1;
+        self.encoded_bytes += size_of::<u64>() + BitPage::encoded_len(bit_page);
+        if bit_page == mask {
+            self.full_pages += 1;
+        }
+
+        self.runs += (bit_page & !(bit_page << 1)).count_ones() as usize;
+        if let Some((last_idx, last_page)) = self.last {
+            if last_idx + 1 == page_idx && last_page >> 63 == 1 && bit_page & 1 == 1 {
+                // run continues from previous page
+                self.runs -= 1;
+            }
+        }
+        self.last = Some((page_idx, bit_page));
+
+        let bucket_bits = self.bucket_bits;
+        if let Some(ref mut density) = self.density {
+            let start = join_bit_index(page_idx, 0);
+            // buckets of the lowest and highest active bits
+            let (first, last) = (
+                (start + bit_page.trailing_zeros() as u64) / bucket_bits,
+                (start + BitPage::MAX_BITS as u64 - 1 - bit_page.leading_zeros() as u64) / bucket_bits,
+            );
+            if density.len() <= last as usize {
+                density.resize(last as usize + 1, 0);
+            }
+
+            if first == last {
+                density[first as usize] += ones as u64;
+            } else {
+                // page straddles buckets
+                for bit_idx in BitPage::active_bits(bit_page) {
+                    density[((start + bit_idx as u64) / bucket_bits) as usize] += 1;
+                }
+            }
+        }
+    }
+
+    // span_pages are the pages full, empty and mixed pages add upto
+    pub(crate) fn into_stats(
+        self,
+        kind: BitPageVecKind,
+        universe_len: Option<u64>,
+        stored: usize,
+        heap_bytes: usize,
+        span_pages: usize,
+    ) -> BitPageVecStats {
+        BitPageVecStats {
+            kind,
+            universe_len,
+            stored,
+            active_bits: self.active_bits,
+            heap_bytes,
+            // length of the pages
+            encoded_bytes: self.encoded_bytes + if self.active_pages > 0 { size_of::<u64>() } else { 0 },
+            full_pages: self.full_pages,
+            empty_pages: span_pages - self.active_pages,
+            mixed_pages: self.active_pages - self.full_pages,
+            runs: self.runs,
+            bucket_bits: self.bucket_bits,
+            density: self.density,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+
+    use crate::bit_page_vec::BitPageVecKind;
+    use crate::bit_page_vec_stats::BitPageVecStatsAggregate;
+    use crate::{BitPageVec, DbBitPageVec};
+
+    #[test]
+    fn test_stats() {
+        let universe = (10, 10);
+        let bit_page_vec = BitPageVec::from_ranges(vec![0..64, 100..110, 130..200, 640..650], universe);
+
+        let stats = bit_page_vec.stats_with_density(100);
+        assert_eq!(stats.kind, BitPageVecKind::SparseWithZeroesHole);
+        assert_eq!(stats.universe_len, Some(650));
+        assert_eq!(stats.active_bits, 64 + 10 + 70 + 10);
+        assert_eq!(stats.heap_bytes, bit_page_vec.estimated_bytes());
+        // pages 0 and 10 (the partial tail) are full
+        assert_eq!((stats.full_pages, stats.mixed_pages, stats.empty_pages), (2, 3, 6));
+        assert_eq!(stats.runs, 4);
+        assert_eq!(stats.density, Some(vec![64, 80, 0, 0, 0, 0, 10]));
+
+        // encoded size is what DbBitPageVec writes for the same pages
+        let db_bit_page_vec = bit_page_vec.iter().collect::<DbBitPageVec>();
+        let mut buf = BytesMut::new();
+        db_bit_page_vec.encode(&mut buf);
+        assert_eq!(stats.encoded_bytes, buf.len());
+        assert_eq!(db_bit_page_vec.stats().encoded_bytes, buf.len());
+        assert_eq!(db_bit_page_vec.stats().active_bits, stats.active_bits);
+
+        let all_ones = BitPageVec::all_ones(universe).stats();
+        assert_eq!(
+            (all_ones.active_bits, all_ones.full_pages, all_ones.runs, all_ones.heap_bytes),
+            (650, 11, 1, 0)
+        );
+        assert_eq!(all_ones.density, None);
+
+        let aggregate = [stats, all_ones, BitPageVec::all_zeros(universe).stats_with_density(100)]
+            .iter()
+            .collect::<BitPageVecStatsAggregate>();
+        assert_eq!(aggregate.vectors, 3);
+        assert_eq!(aggregate.count_of(BitPageVecKind::AllOnes), 1);
+        assert_eq!(aggregate.active_bits, 154 + 650);
+        assert_eq!(aggregate.universe_len, 3 * 650);
+        assert_eq!(aggregate.density, Some(vec![64, 80, 0, 0, 0, 0, 10]));
+    }
+}
@@ -1,7 +1,8 @@
 use std::cmp::{max, min};
+use std::sync::Arc;
 
 use crate::bit_page_vec_iter::BitPageVecIter;
-use crate::BitPageVec;
+use crate::{BitPageVec, CompactionPolicy, UniversePolicy};
 
 // @author shailendra.sharma
 
@@ -18,6 +19,7 @@ pub enum BooleanOp<'a> {
 pub struct BooleanOpResult<'a> {
     len: usize,
     iter: BitPageVecIter<'a>,
+    compaction_policy: Option<Arc<dyn CompactionPolicy>>,
 }
 
 impl<'a> BooleanOp<'a> {
@@ -56,21 +58,34 @@ impl<'a> BooleanOp<'a> {
     }
 
     pub fn evaluate(self) -> BooleanOpResult<'a> {
+        self.evaluate_with(UniversePolicy::default())
+    }
+
+    /// same as evaluate... operands of different universes are combined as per universe_policy
+    pub fn evaluate_with(self, universe_policy: UniversePolicy) -> BooleanOpResult<'a> {
         // if log_enabled!(target: "bit_page_vec_log", Level::Debug) {
         //     debug!(target: "bit_page_vec_log", "evaluate boolean_op={:?}", self);
         // }
 
         let result = match self {
-            BooleanOp::And(ops) => ops.into_iter().map(|op| op.evaluate()).and_merge_leaves(),
-            BooleanOp::Or(ops) => ops.into_iter().map(|op| op.evaluate()).or_merge_leaves(),
-            BooleanOp::Not(op) => op.evaluate().not(),
+            BooleanOp::And(ops) => ops
+                .into_iter()
+                .map(|op| op.evaluate_with(universe_policy))
+                .and_merge_leaves(universe_policy),
+            BooleanOp::Or(ops) => ops
+                .into_iter()
+                .map(|op| op.evaluate_with(universe_policy))
+                .or_merge_leaves(universe_policy),
+            BooleanOp::Not(op) => op.evaluate_with(universe_policy).not(),
             BooleanOp::BorrowedLeaf(leaf) => BooleanOpResult {
                 len: leaf.size(),
-                iter: leaf.iter(),
+                iter: leaf.page_iter(),
+                compaction_policy: None,
             },
             BooleanOp::OwnedLeaf(leaf) => BooleanOpResult {
                 len: leaf.size(),
-                iter: leaf.into_iter(),
+                iter: leaf.into_page_iter(),
+                compaction_policy: None,
             },
         };
 
@@ -122,28 +137,29 @@ impl<'a> BooleanOp<'a> {
 }
 
 pub trait MergeLeavesIterator<'a>: Iterator<Item = BooleanOpResult<'a>> {
-    fn and_merge_leaves(self) -> BooleanOpResult<'a>
+    fn and_merge_leaves(self, universe_policy: UniversePolicy) -> BooleanOpResult<'a>
     where
         Self: Sized,
     {
         let mut merged_iter: Option<BitPageVecIter<'a>> = None;
 
-        let mut len = usize::max_value();
+        let mut len = usize::MAX;
         for leaf in self.into_iter() {
             len = min(len, leaf.len);
             match merged_iter {
                 None => merged_iter = Some(leaf.iter),
-                Some(first) => merged_iter = Some(BitPageVecIter::and(first, leaf.iter)),
+                Some(first) => merged_iter = Some(BitPageVecIter::and_with(first, leaf.iter, universe_policy)),
             }
         }
 
         BooleanOpResult {
             len,
             iter: merged_iter.unwrap(),
+            compaction_policy: None,
         }
     }
 
-    fn or_merge_leaves(self) -> BooleanOpResult<'a>
+    fn or_merge_leaves(self, universe_policy: UniversePolicy) -> BooleanOpResult<'a>
     where
         Self: Sized,
     {
@@ -153,14 +169,15 @@ pub trait MergeLeavesIterator<'a>: Iterator<Item = BooleanOpResult<'a>> {
         for leaf in self.into_iter() {
             len = max(len, leaf.len);
             match merged_iter {
-                None => merged_iter = { Some(leaf.iter) },
-                Some(first) => merged_iter = Some(BitPageVecIter::or(first, leaf.iter)),
+                None => merged_iter = Some(leaf.iter),
+                Some(first) => merged_iter = Some(BitPageVecIter::or_with(first, leaf.iter, universe_policy)),
             }
         }
 
         BooleanOpResult {
             len,
             iter: merged_iter.unwrap(),
+            compaction_policy: None,
         }
     }
 }
@@ -168,14 +185,73 @@ pub trait MergeLeavesIterator<'a>: Iterator<Item = BooleanOpResult<'a>> {
 impl<'a, T: ?Sized> MergeLeavesIterator<'a> for T where T: Iterator<Item = BooleanOpResult<'a>> {}
 
 impl<'a> BooleanOpResult<'a> {
+    /// compaction of the evaluated vector... the policy stays with it for later operations
+    pub fn with_compaction_policy(mut self, compaction_policy: Arc<dyn CompactionPolicy>) -> BooleanOpResult<'a> {
+        self.compaction_policy = Some(compaction_policy);
+        self
+    }
+
     pub fn into_bit_page_vec(self) -> BitPageVec {
-        self.iter.into_bit_page_vec()
+        match self.compaction_policy {
+            Some(compaction_policy) => self
+                .iter
+                .into_bit_page_vec_with(compaction_policy.as_ref())
+                .with_compaction_policy(compaction_policy),
+            None => self.iter.into_bit_page_vec(),
+        }
     }
 
     // how to do this in fluent pattern... looks like it is hard in Rust (to google later)
     fn not(self) -> BooleanOpResult<'a> {
         let iter = self.iter.not();
 
-        BooleanOpResult { len: self.len, iter }
+        BooleanOpResult {
+            len: self.len,
+            iter,
+            compaction_policy: self.compaction_policy,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bit_page_vec_fixtures::{allocations, vectors};
+    use crate::{BitPageVec, BooleanOp};
+
+    // or of ands, nots and nested ors over every kind (but all zeroes/ones, which short circuit)... 14 nodes
+    fn deep_op(vectors: &[BitPageVec]) -> BooleanOp<'_> {
+        let leaf = |idx: usize| BooleanOp::new_leaf_op(&vectors[idx]);
+        let not = |op| BooleanOp::new_not_op(op);
+        let and = |ops| BooleanOp::new_and_op(ops).unwrap();
+        let or = |ops| BooleanOp::new_or_op(ops).unwrap();
+
+        or(vec![
+            and(vec![leaf(2), leaf(5), not(leaf(4))]),
+            and(vec![leaf(3), leaf(5)]),
+            and(vec![or(vec![leaf(2), leaf(4)]), not(leaf(3))]),
+            not(and(vec![leaf(5), leaf(4)])),
+        ])
+    }
+
+    #[test]
+    fn test_allocations_do_not_grow_with_pages() {
+        // every universe spans more than one region of containers... so their decode buffers are grown the same way
+        let counts = [(2000, 0), (5000, 7), (20_000, 3)]
+            .iter()
+            .map(|universe| {
+                let vectors = vectors(*universe);
+                let op = deep_op(&vectors);
+
+                let (result, evaluate) = allocations(|| op.evaluate());
+                let (ones, stream) = allocations(|| result.iter.count_ones());
+                assert!(ones > 0);
+
+                (evaluate, stream)
+            })
+            .collect::<Vec<_>>();
+
+        // at most one box per node of the tree (while it is built)... and nothing per page while streaming
+        assert!(counts[0].0 <= 14, "{:?}", counts);
+        assert!(counts.iter().all(|count| *count == counts[0]), "{:?}", counts);
     }
 }
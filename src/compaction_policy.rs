@@ -0,0 +1,169 @@
+use std::fmt;
+
+// @author shailendra.sharma
+use crate::bit_page_vec::BitPageVecKind;
+use crate::Universe;
+
+/// shape of the pages a compaction decision is made on
+#[derive(Copy, Clone, Debug)]
+pub struct CompactionStats {
+    // hole of the pages... either SparseWithZeroesHole or SparseWithOnesHole
+    pub kind: BitPageVecKind,
+    pub universe: Universe,
+    // stored (i.e. non hole) pages
+    pub pages: usize,
+    // pages from the first stored page to the last one
+    pub span: usize,
+    // active bits of the stored pages
+    pub ones: u64,
+    // runs of active bits across the stored pages (zeroes hole only... 0 otherwise)
+    pub runs: usize,
+}
+
+/// decides how a sparse vector gets stored after it is built (or rebuilt by an operation)
+pub trait CompactionPolicy: fmt::Debug + Send + Sync {
+    /// representation of non-empty pages... AllZeroes and AllOnes keep the pages as they are
+    fn target(&self, stats: &CompactionStats) -> BitPageVecKind;
+
+    /// sees every decision (including empty pages which always become AllZeroes or AllOnes)... e.g. for metrics
+    fn observe(&self, _stats: &CompactionStats, _target: BitPageVecKind) {}
+}
+
+/// thresholds based policy... Default is what every vector used before policies were pluggable
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DefaultCompactionPolicy {
+    // zeroes hole pages become runs when there are at least these many pages per run
+    pub runs_pages_ratio: usize,
+    // zeroes hole pages become containers when there are at most these many active bits per page
+    pub container_ones_per_page: usize,
+    // upto these many pages are kept as they are
+    pub max_sparse_pages: usize,
+    // hole is only swapped when stored pages cover at least this share of their span
+    pub span_ratio: f64,
+    // zeroes hole swaps to ones hole when active bits are at least this share of the span bits
+    pub dense_ratio: f64,
+    // ones hole swaps to zeroes hole when active bits are at most this share of the span bits
+    pub sparse_ratio: f64,
+}
+
+pub(crate) static DEFAULT_COMPACTION_POLICY: DefaultCompactionPolicy = DefaultCompactionPolicy::new();
+
+impl DefaultCompactionPolicy {
+    pub const fn new() -> DefaultCompactionPolicy {
+        DefaultCompactionPolicy {
+            runs_pages_ratio: 8,
+            container_ones_per_page: 4,
+            max_sparse_pages: 10_000,
+            span_ratio: 0.75,
+            dense_ratio: 0.75,
+            sparse_ratio: 0.25,
+        }
+    }
+}
+
+impl Default for DefaultCompactionPolicy {
+    fn default() -> Self {
+        DefaultCompactionPolicy::new()
+    }
+}
+
+impl CompactionPolicy for DefaultCompactionPolicy {
+    fn target(&self, stats: &CompactionStats) -> BitPageVecKind {
+        let span_bits = stats.span as f64 * 64.0;
+        let dense_span = stats.pages as f64 >= self.span_ratio * stats.span as f64;
+
+        match stats.kind {
+            BitPageVecKind::SparseWithOnesHole => {
+                if stats.pages > self.max_sparse_pages && dense_span && stats.ones as f64 <= self.sparse_ratio * span_bits {
+                    BitPageVecKind::SparseWithZeroesHole
+                } else {
+                    BitPageVecKind::SparseWithOnesHole
+                }
+            }
+            _ => {
+                if stats.runs.saturating_mul(self.runs_pages_ratio) <= stats.pages {
+                    // few long runs of active bits... far cheaper to keep runs than pages
+                    BitPageVecKind::Runs
+                } else if stats.ones <= self.container_ones_per_page.saturating_mul(stats.pages) as u64 {
+                    // very few active bits per page... sorted offsets are cheaper than pages
+                    BitPageVecKind::Containers
+                } else if stats.pages > self.max_sparse_pages && dense_span && stats.ones as f64 >= self.dense_ratio * span_bits {
+                    BitPageVecKind::SparseWithOnesHole
+                } else {
+                    BitPageVecKind::SparseWithZeroesHole
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use itertools::Itertools;
+
+    use crate::bit_page_vec::BitPageVecKind;
+    use crate::{BitPageVec, BooleanOp, CompactionPolicy, CompactionStats, DefaultCompactionPolicy};
+
+    // keeps pages as they are and records what it was asked
+    #[derive(Debug, Default)]
+    struct KeepSparse {
+        decisions: Mutex<Vec<(usize, BitPageVecKind)>>,
+    }
+
+    impl CompactionPolicy for KeepSparse {
+        fn target(&self, stats: &CompactionStats) -> BitPageVecKind {
+            stats.kind
+        }
+
+        fn observe(&self, stats: &CompactionStats, target: BitPageVecKind) {
+            self.decisions.lock().unwrap().push((stats.pages, target));
+        }
+    }
+
+    #[test]
+    fn test_compaction_policy() {
+        let universe = (100, 0);
+        let first = BitPageVec::from_ranges(vec![0..1000, 1000..3000], universe);
+        let second = BitPageVec::from_ranges(vec![64..3200, 3200..6400], universe);
+
+        // default policy keeps long runs of active bits as runs
+        let mut and = first.clone();
+        and.and(&second);
+        assert!(matches!(and.kind(), BitPageVecKind::Runs));
+
+        let policy = Arc::new(KeepSparse::default());
+
+        // per vector
+        let mut and = first.clone().with_compaction_policy(policy.clone());
+        and.and(&second);
+        assert!(matches!(and.kind(), BitPageVecKind::SparseWithZeroesHole));
+        assert_eq!(and.iter().collect_vec(), (64..3000).collect_vec());
+
+        // per boolean op evaluation
+        let op = BooleanOp::new_and_op(vec![BooleanOp::new_leaf_op(&first), BooleanOp::new_leaf_op(&second)]).unwrap();
+        let result = op.evaluate().with_compaction_policy(policy.clone()).into_bit_page_vec();
+        assert!(matches!(result.kind(), BitPageVecKind::SparseWithZeroesHole));
+        assert_eq!(result, and);
+
+        let decisions = policy.decisions.lock().unwrap().clone();
+        assert_eq!(decisions.len(), 2);
+        assert!(decisions
+            .iter()
+            .all(|(pages, target)| *pages == 46 && matches!(target, BitPageVecKind::SparseWithZeroesHole)));
+
+        // thresholds can be tuned... e.g. no runs at all
+        let no_runs = Arc::new(DefaultCompactionPolicy {
+            runs_pages_ratio: usize::MAX,
+            ..DefaultCompactionPolicy::default()
+        });
+        let mut or = BitPageVec::all_zeros(universe);
+        or.or(&first);
+        assert!(matches!(or.kind(), BitPageVecKind::Runs));
+
+        let mut or = BitPageVec::all_zeros(universe).with_compaction_policy(no_runs);
+        or.or(&first);
+        assert!(matches!(or.kind(), BitPageVecKind::SparseWithZeroesHole));
+    }
+}
@@ -0,0 +1,64 @@
+use itertools::{EitherOrBoth, Itertools};
+
+// @author shailendra.sharma
+use crate::bit_page::BitPageWithPosition;
+use crate::bit_page_vec_batch::batch_pages;
+use crate::DbBitPageVec;
+
+impl DbBitPageVec {
+    /// same as BitPageVec::apply_batch... an id present in both sets and clears gets cleared
+    pub fn apply_batch<S, C>(&mut self, sets: S, clears: C)
+    where
+        S: IntoIterator<Item = u64>,
+        C: IntoIterator<Item = u64>,
+    {
+        let (sets, clears) = (batch_pages(sets).into_pages(), batch_pages(clears).into_pages());
+
+        let pages = self
+            .pages()
+            .iter()
+            .merge_join_by(sets.iter(), |page_1, page_2| page_1.page_idx.cmp(&page_2.page_idx))
+            .map(|either| match either {
+                EitherOrBoth::Both(page_one, page_two) => BitPageWithPosition {
+                    page_idx: page_one.page_idx,
+                    bit_page: page_one.bit_page | page_two.bit_page,
+                },
+                EitherOrBoth::Left(page) | EitherOrBoth::Right(page) => page.clone(),
+            })
+            .merge_join_by(clears.iter(), |page_1, page_2| page_1.page_idx.cmp(&page_2.page_idx))
+            .filter_map(|either| match either {
+                EitherOrBoth::Both(page_one, page_two) => Some(BitPageWithPosition {
+                    page_idx: page_one.page_idx,
+                    bit_page: page_one.bit_page & !page_two.bit_page,
+                }),
+                EitherOrBoth::Left(page) => Some(page),
+                EitherOrBoth::Right(_) => None,
+            })
+            .filter(|page| page.bit_page != 0)
+            .collect_vec();
+
+        *self = if pages.is_empty() {
+            DbBitPageVec::all_zeros()
+        } else {
+            DbBitPageVec::Sparse(pages)
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::DbBitPageVec;
+
+    #[test]
+    fn test_apply_batch() {
+        let mut db_bit_page_vec = [3u64, 64, 65, 1000].iter().copied().collect::<DbBitPageVec>();
+
+        db_bit_page_vec.apply_batch([700, 2, 65, 5000], [64, 65, 999, 5000]);
+        assert_eq!(db_bit_page_vec.iter().collect_vec(), vec![2, 3, 700, 1000]);
+
+        db_bit_page_vec.apply_batch(None, [2, 3, 700, 1000]);
+        assert!(matches!(db_bit_page_vec, DbBitPageVec::AllZeroes));
+    }
+}
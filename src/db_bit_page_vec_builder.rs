@@ -0,0 +1,68 @@
+// @author shailendra.sharma
+use crate::bit_page_vec_builder::BuilderPages;
+use crate::DbBitPageVec;
+
+/// same as BitPageVecBuilder... for DbBitPageVec
+#[derive(Default)]
+pub struct DbBitPageVecBuilder {
+    pages: BuilderPages,
+}
+
+impl DbBitPageVecBuilder {
+    pub fn new() -> DbBitPageVecBuilder {
+        DbBitPageVecBuilder::default()
+    }
+
+    #[inline]
+    pub fn push(&mut self, index: u64) {
+        self.pages.push(index);
+    }
+
+    /// active bits of bit_page are added to page page_idx
+    #[inline]
+    pub fn push_word(&mut self, page_idx: usize, bit_page: u64) {
+        self.pages.push_word(page_idx, bit_page);
+    }
+
+    pub fn finish(self) -> DbBitPageVec {
+        let pages = self.pages.into_pages();
+
+        if pages.is_empty() {
+            DbBitPageVec::all_zeros()
+        } else {
+            DbBitPageVec::Sparse(pages)
+        }
+    }
+}
+
+impl Extend<u64> for DbBitPageVecBuilder {
+    fn extend<T: IntoIterator<Item = u64>>(&mut self, iter: T) {
+        for index in iter {
+            self.push(index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::{DbBitPageVec, DbBitPageVecBuilder};
+
+    #[test]
+    fn test_builder() {
+        let mut builder = DbBitPageVecBuilder::new();
+        builder.extend(vec![1, 3, 64, 64]);
+        builder.push_word(2, 0b101);
+        assert_eq!(builder.finish().iter().collect_vec(), vec![1, 3, 64, 128, 130]);
+
+        // out of order pushes... pages are sorted and merged on finish
+        let mut builder = DbBitPageVecBuilder::new();
+        builder.extend(vec![700, 5, 130, 6, 700]);
+        builder.push_word(0, 0b1);
+        builder.push_word(10, 0b10);
+        assert_eq!(builder.finish().iter().collect_vec(), vec![0, 5, 6, 130, 641, 700]);
+
+        assert!(matches!(DbBitPageVecBuilder::new().finish(), DbBitPageVec::AllZeroes));
+    }
+}
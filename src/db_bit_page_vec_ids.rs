@@ -0,0 +1,146 @@
+use std::iter::FromIterator;
+
+// @author shailendra.sharma
+use crate::bit_page::{split_bit_index, BitPageWithPosition};
+use crate::bit_page_vec_ids::{pages_from_sorted_ids, BitPageVecIdsIterator};
+use crate::bit_page_vec_iter::PageIterator;
+use crate::DbBitPageVec;
+
+impl DbBitPageVec {
+    #[inline]
+    pub fn insert(&mut self, index: u64) {
+        let (page_idx, bit_idx) = split_bit_index(index);
+        self.set_bit(page_idx, bit_idx);
+    }
+
+    #[inline]
+    pub fn remove(&mut self, index: u64) {
+        let (page_idx, bit_idx) = split_bit_index(index);
+        self.clear_bit(page_idx, bit_idx);
+    }
+
+    #[inline]
+    pub fn contains(&self, index: u64) -> bool {
+        let (page_idx, bit_idx) = split_bit_index(index);
+        self.is_bit_set(page_idx, bit_idx)
+    }
+
+    /// ids of all active bits in ascending order
+    pub fn iter(&self) -> BitPageVecIdsIterator<'_> {
+        match self {
+            DbBitPageVec::AllZeroes => BitPageVecIdsIterator::None,
+            DbBitPageVec::Sparse(pages) => BitPageVecIdsIterator::from_pages::<PageIterator>(Box::new(
+                pages
+                    .iter()
+                    .map(|BitPageWithPosition { page_idx, bit_page }| (*page_idx, *bit_page)),
+            )),
+        }
+    }
+
+    /// ids of all active bits in descending order
+    pub fn iter_rev(&self) -> BitPageVecIdsIterator<'_> {
+        match self {
+            DbBitPageVec::AllZeroes => BitPageVecIdsIterator::None,
+            DbBitPageVec::Sparse(pages) => BitPageVecIdsIterator::from_pages_rev::<PageIterator>(Box::new(
+                pages
+                    .iter()
+                    .rev()
+                    .map(|BitPageWithPosition { page_idx, bit_page }| (*page_idx, *bit_page)),
+            )),
+        }
+    }
+}
+
+impl FromIterator<u64> for DbBitPageVec {
+    fn from_iter<T: IntoIterator<Item = u64>>(iter: T) -> Self {
+        let mut ids = iter.into_iter().collect::<Vec<_>>();
+        ids.sort_unstable();
+        ids.dedup();
+
+        if ids.is_empty() {
+            DbBitPageVec::all_zeros()
+        } else {
+            DbBitPageVec::Sparse(pages_from_sorted_ids(ids))
+        }
+    }
+}
+
+impl Extend<u64> for DbBitPageVec {
+    fn extend<T: IntoIterator<Item = u64>>(&mut self, iter: T) {
+        for index in iter {
+            self.insert(index);
+        }
+    }
+}
+
+impl IntoIterator for DbBitPageVec {
+    type Item = u64;
+    type IntoIter = BitPageVecIdsIterator<'static>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        match self {
+            DbBitPageVec::AllZeroes => BitPageVecIdsIterator::None,
+            DbBitPageVec::Sparse(pages) => BitPageVecIdsIterator::from_pages::<PageIterator>(Box::new(
+                pages
+                    .into_iter()
+                    .map(|BitPageWithPosition { page_idx, bit_page }| (page_idx, bit_page)),
+            )),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a DbBitPageVec {
+    type Item = u64;
+    type IntoIter = BitPageVecIdsIterator<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::DbBitPageVec;
+
+    #[test]
+    fn test_insert_remove_contains() {
+        let mut db_bit_page_vec = DbBitPageVec::all_zeros();
+        assert_eq!(db_bit_page_vec.iter().next(), None);
+
+        for id in &[639, 0, 64, 63, 65] {
+            db_bit_page_vec.insert(*id);
+        }
+
+        assert!(db_bit_page_vec.contains(63));
+        assert!(db_bit_page_vec.contains(639));
+        assert!(!db_bit_page_vec.contains(66));
+        assert!(!db_bit_page_vec.contains(1 << 40));
+
+        db_bit_page_vec.remove(64);
+        assert!(!db_bit_page_vec.contains(64));
+        assert_eq!(db_bit_page_vec.iter().collect_vec(), vec![0, 63, 65, 639]);
+        assert_eq!(db_bit_page_vec.iter_rev().collect_vec(), vec![639, 65, 63, 0]);
+
+        for id in &[0, 63, 65, 639] {
+            db_bit_page_vec.remove(*id);
+        }
+        assert!(matches!(db_bit_page_vec, DbBitPageVec::AllZeroes));
+    }
+
+    #[test]
+    fn test_from_iter_extend_and_into_iter() {
+        let db_bit_page_vec = vec![700, 3, 64, 3, 128].into_iter().collect::<DbBitPageVec>();
+        assert_eq!((&db_bit_page_vec).into_iter().collect_vec(), vec![3, 64, 128, 700]);
+
+        let mut extended = db_bit_page_vec.clone();
+        extended.extend(vec![5000, 4]);
+        assert_eq!(extended.into_iter().collect_vec(), vec![3, 4, 64, 128, 700, 5000]);
+
+        assert!(matches!(
+            Vec::<u64>::new().into_iter().collect::<DbBitPageVec>(),
+            DbBitPageVec::AllZeroes
+        ));
+    }
+}
@@ -0,0 +1,84 @@
+// @author shailendra.sharma
+use crate::bit_page::split_bit_index;
+use crate::bit_page_vec_navigate::{next_in_pages, prev_in_pages};
+use crate::{BitPage, DbBitPageVec};
+
+impl DbBitPageVec {
+    /// smallest active id
+    pub fn min(&self) -> Option<u64> {
+        self.next_set_bit(0)
+    }
+
+    /// largest active id
+    pub fn max(&self) -> Option<u64> {
+        self.prev_set_bit(u64::MAX)
+    }
+
+    /// smallest active id >= from
+    pub fn next_set_bit(&self, from: u64) -> Option<u64> {
+        self.next_bit(from, true)
+    }
+
+    /// largest active id <= from
+    pub fn prev_set_bit(&self, from: u64) -> Option<u64> {
+        self.prev_bit(from, true)
+    }
+
+    /// smallest inactive id >= from
+    pub fn next_clear_bit(&self, from: u64) -> Option<u64> {
+        self.next_bit(from, false)
+    }
+
+    /// largest inactive id <= from
+    pub fn prev_clear_bit(&self, from: u64) -> Option<u64> {
+        self.prev_bit(from, false)
+    }
+
+    fn next_bit(&self, from: u64, set: bool) -> Option<u64> {
+        let pages = self.pages();
+        let position = pages.partition_point(|page| page.page_idx < split_bit_index(from).0);
+
+        let iter = pages[position..].iter().map(|page| (page.page_idx, page.bit_page));
+        next_in_pages(iter, BitPage::zeroes(), from, set)
+    }
+
+    fn prev_bit(&self, from: u64, set: bool) -> Option<u64> {
+        let pages = self.pages();
+        let position = pages.partition_point(|page| page.page_idx <= split_bit_index(from).0);
+
+        let iter = pages[..position].iter().rev().map(|page| (page.page_idx, page.bit_page));
+        prev_in_pages(iter, BitPage::zeroes(), from, set)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::DbBitPageVec;
+
+    #[test]
+    fn test_navigate() {
+        // two full pages... then a gap of a page which is not stored
+        let db_bit_page_vec = DbBitPageVec::from_ranges(vec![0..128, 200..201, 260..262]);
+
+        assert_eq!(db_bit_page_vec.min(), Some(0));
+        assert_eq!(db_bit_page_vec.max(), Some(261));
+
+        assert_eq!(db_bit_page_vec.next_set_bit(128), Some(200));
+        assert_eq!(db_bit_page_vec.next_set_bit(262), None);
+        assert_eq!(db_bit_page_vec.prev_set_bit(199), Some(127));
+        assert_eq!(db_bit_page_vec.prev_set_bit(u64::MAX), Some(261));
+
+        assert_eq!(db_bit_page_vec.next_clear_bit(0), Some(128));
+        assert_eq!(db_bit_page_vec.next_clear_bit(200), Some(201));
+        assert_eq!(db_bit_page_vec.next_clear_bit(260), Some(262));
+        assert_eq!(db_bit_page_vec.prev_clear_bit(127), None);
+        assert_eq!(db_bit_page_vec.prev_clear_bit(261), Some(259));
+        assert_eq!(db_bit_page_vec.prev_clear_bit(200), Some(199));
+
+        let empty = DbBitPageVec::all_zeros();
+        assert_eq!(empty.min(), None);
+        assert_eq!(empty.max(), None);
+        assert_eq!(empty.next_clear_bit(77), Some(77));
+        assert_eq!(empty.prev_set_bit(77), None);
+    }
+}
@@ -0,0 +1,76 @@
+use std::mem::{size_of_val, take};
+
+// @author shailendra.sharma
+use crate::bit_page_vec::BitPageVecKind;
+use crate::{DbBitPageVec, OptimizeReport};
+
+impl DbBitPageVec {
+    /// drops empty pages... an empty vector becomes AllZeroes (reported as SparseWithZeroesHole while it has pages)
+    pub fn optimize(&mut self) -> OptimizeReport {
+        let (kind_before, bytes_before) = (self.kind(), self.estimated_bytes());
+
+        if let DbBitPageVec::Sparse(pages) = self {
+            let mut pages = take(pages);
+            pages.retain(|page| page.bit_page != 0);
+
+            *self = if pages.is_empty() {
+                DbBitPageVec::all_zeros()
+            } else {
+                DbBitPageVec::Sparse(pages)
+            };
+        }
+
+        OptimizeReport {
+            kind_before,
+            kind_after: self.kind(),
+            bytes_before,
+            bytes_after: self.estimated_bytes(),
+        }
+    }
+
+    /// estimated heap bytes of the stored pages
+    pub fn estimated_bytes(&self) -> usize {
+        size_of_val(self.pages())
+    }
+
+    pub(crate) fn kind(&self) -> BitPageVecKind {
+        match self {
+            DbBitPageVec::AllZeroes => BitPageVecKind::AllZeroes,
+            DbBitPageVec::Sparse(_) => BitPageVecKind::SparseWithZeroesHole,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem::size_of;
+
+    use itertools::Itertools;
+
+    use crate::bit_page::BitPageWithPosition;
+    use crate::{BitPageVecKind, DbBitPageVec};
+
+    #[test]
+    fn test_optimize() {
+        let page = |page_idx, bit_page| BitPageWithPosition { page_idx, bit_page };
+
+        let mut db_bit_page_vec = DbBitPageVec::Sparse(vec![page(0, 0), page(1, 0b11), page(5, 0)]);
+        let report = db_bit_page_vec.optimize();
+        assert_eq!(report.kind_before, BitPageVecKind::SparseWithZeroesHole);
+        assert_eq!(report.kind_after, BitPageVecKind::SparseWithZeroesHole);
+        assert_eq!(report.bytes_before, 3 * size_of::<BitPageWithPosition>());
+        assert_eq!(report.bytes_after, size_of::<BitPageWithPosition>());
+        assert_eq!(db_bit_page_vec.iter().collect_vec(), vec![64, 65]);
+
+        // nothing but empty pages... becomes AllZeroes
+        let mut db_bit_page_vec = DbBitPageVec::Sparse(vec![page(3, 0)]);
+        let report = db_bit_page_vec.optimize();
+        assert_eq!(report.kind_after, BitPageVecKind::AllZeroes);
+        assert_eq!(report.bytes_after, 0);
+        assert!(matches!(db_bit_page_vec, DbBitPageVec::AllZeroes));
+
+        let report = db_bit_page_vec.optimize();
+        assert_eq!(report.kind_before, BitPageVecKind::AllZeroes);
+        assert_eq!(report.bytes_before, 0);
+    }
+}
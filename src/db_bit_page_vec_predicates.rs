@@ -0,0 +1,72 @@
+use itertools::{EitherOrBoth, Itertools};
+
+// @author shailendra.sharma
+use crate::bit_page::BitPageWithPosition;
+use crate::DbBitPageVec;
+
+impl DbBitPageVec {
+    /// true if self and second share at least one active bit
+    pub fn intersects(&self, second: &DbBitPageVec) -> bool {
+        self.pages()
+            .iter()
+            .merge_join_by(second.pages().iter(), |page_1, page_2| page_1.page_idx.cmp(&page_2.page_idx))
+            .any(|either| match either {
+                EitherOrBoth::Both(page_one, page_two) => page_one.bit_page & page_two.bit_page != 0,
+                EitherOrBoth::Left(_) | EitherOrBoth::Right(_) => false,
+            })
+    }
+
+    pub fn is_disjoint(&self, second: &DbBitPageVec) -> bool {
+        !self.intersects(second)
+    }
+
+    /// true if every active bit of self is active in second
+    pub fn is_subset(&self, second: &DbBitPageVec) -> bool {
+        self.pages()
+            .iter()
+            .merge_join_by(second.pages().iter(), |page_1, page_2| page_1.page_idx.cmp(&page_2.page_idx))
+            .all(|either| match either {
+                EitherOrBoth::Both(page_one, page_two) => page_one.bit_page & !page_two.bit_page == 0,
+                EitherOrBoth::Left(page_one) => page_one.bit_page == 0,
+                EitherOrBoth::Right(_) => true,
+            })
+    }
+
+    pub fn is_superset(&self, second: &DbBitPageVec) -> bool {
+        second.is_subset(self)
+    }
+
+    pub(crate) fn pages(&self) -> &[BitPageWithPosition] {
+        match self {
+            DbBitPageVec::AllZeroes => &[],
+            DbBitPageVec::Sparse(pages) => pages,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::DbBitPageVec;
+
+    #[test]
+    fn test_predicates() {
+        let first = vec![1, 64, 700].into_iter().collect::<DbBitPageVec>();
+        let second = vec![1, 2, 64, 65, 700, 5000].into_iter().collect::<DbBitPageVec>();
+        let apart = vec![0, 66, 701].into_iter().collect::<DbBitPageVec>();
+        let empty = DbBitPageVec::all_zeros();
+
+        assert!(first.intersects(&second));
+        assert!(first.is_subset(&second));
+        assert!(second.is_superset(&first));
+        assert!(!second.is_subset(&first));
+
+        // same pages but no shared bits
+        assert!(first.is_disjoint(&apart));
+        assert!(!apart.is_subset(&second));
+
+        assert!(!empty.intersects(&first));
+        assert!(empty.is_subset(&first));
+        assert!(!first.is_subset(&empty));
+        assert!(empty.is_subset(&empty));
+    }
+}
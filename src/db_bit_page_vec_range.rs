@@ -0,0 +1,81 @@
+use std::ops::Range;
+
+// @author shailendra.sharma
+use crate::bit_page_vec_range::{apply_range_op, coalesce_ranges, pages_from_sorted_ranges, RangeOp};
+use crate::{BitPage, DbBitPageVec};
+
+impl DbBitPageVec {
+    pub fn set_range(&mut self, range: Range<u64>) {
+        self.apply_range(range, RangeOp::Set);
+    }
+
+    pub fn clear_range(&mut self, range: Range<u64>) {
+        self.apply_range(range, RangeOp::Clear);
+    }
+
+    pub fn flip_range(&mut self, range: Range<u64>) {
+        self.apply_range(range, RangeOp::Flip);
+    }
+
+    pub fn from_ranges<I>(ranges: I) -> DbBitPageVec
+    where
+        I: IntoIterator<Item = Range<u64>>,
+    {
+        let pages = pages_from_sorted_ranges(coalesce_ranges(ranges));
+
+        if pages.is_empty() {
+            DbBitPageVec::all_zeros()
+        } else {
+            DbBitPageVec::Sparse(pages)
+        }
+    }
+
+    fn apply_range(&mut self, range: Range<u64>, op: RangeOp) {
+        if range.start >= range.end {
+            return;
+        }
+
+        let pages = match std::mem::take(self) {
+            DbBitPageVec::AllZeroes => Vec::new(),
+            DbBitPageVec::Sparse(pages) => pages,
+        };
+
+        let pages = apply_range_op(pages, BitPage::zeroes(), range, op);
+
+        if !pages.is_empty() {
+            *self = DbBitPageVec::Sparse(pages);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::DbBitPageVec;
+
+    #[test]
+    fn test_ranges() {
+        // unsorted and overlapping ranges are coalesced
+        let mut db_bit_page_vec = DbBitPageVec::from_ranges(vec![130..140, 60..70, 65..68]);
+        assert_eq!(db_bit_page_vec.iter().collect_vec(), (60..70).chain(130..140).collect_vec());
+        assert!(matches!(DbBitPageVec::from_ranges(vec![5..5, 9..9]), DbBitPageVec::AllZeroes));
+
+        db_bit_page_vec.set_range(62..200);
+        assert_eq!(db_bit_page_vec.iter().collect_vec(), (60..200).collect_vec());
+
+        db_bit_page_vec.clear_range(64..192);
+        assert_eq!(db_bit_page_vec.iter().collect_vec(), (60..64).chain(192..200).collect_vec());
+
+        db_bit_page_vec.flip_range(62..194);
+        assert_eq!(db_bit_page_vec.iter().collect_vec(), (60..62).chain(64..192).chain(194..200).collect_vec());
+
+        // empty ranges change nothing
+        db_bit_page_vec.clear_range(100..100);
+        db_bit_page_vec.set_range(300..300);
+        assert_eq!(db_bit_page_vec.iter().count(), 2 + 128 + 6);
+
+        db_bit_page_vec.clear_range(0..1 << 20);
+        assert!(matches!(db_bit_page_vec, DbBitPageVec::AllZeroes));
+    }
+}
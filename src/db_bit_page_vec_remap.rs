@@ -0,0 +1,69 @@
+// @author shailendra.sharma
+use crate::bit_page::BitPageWithPosition;
+use crate::bit_page_vec_ids::pages_from_sorted_ids;
+use crate::bit_page_vec_iter::PageIterator;
+use crate::bit_page_vec_remap::{remap_ids, remap_ids_with};
+use crate::DbBitPageVec;
+
+impl DbBitPageVec {
+    /// same as BitPageVec::remap... for bulk rewrite of stored vectors
+    pub fn remap(&self, deleted: &DbBitPageVec) -> DbBitPageVec {
+        let pages = pages_from_sorted_ids(remap_ids(self.pages_iter(), deleted.pages_iter()));
+
+        if pages.is_empty() {
+            DbBitPageVec::all_zeros()
+        } else {
+            DbBitPageVec::Sparse(pages)
+        }
+    }
+
+    /// same as BitPageVec::remap_with
+    pub fn remap_with(&self, mapping: &[u64]) -> DbBitPageVec {
+        remap_ids_with(self.iter(), mapping).collect()
+    }
+
+    fn pages_iter(&self) -> PageIterator<'_> {
+        match self {
+            DbBitPageVec::AllZeroes => Box::new(std::iter::empty()),
+            DbBitPageVec::Sparse(pages) => Box::new(
+                pages
+                    .iter()
+                    .map(|BitPageWithPosition { page_idx, bit_page }| (*page_idx, *bit_page)),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::{DbBitPageVec, REMAP_DROPPED};
+
+    #[test]
+    fn test_remap() {
+        let db_bit_page_vec = vec![0, 5, 64, 70, 130, 700].into_iter().collect::<DbBitPageVec>();
+        let deleted = vec![1, 5, 65, 129].into_iter().collect::<DbBitPageVec>();
+
+        // deleted ids vanish and the rest move down by the deleted ids before them
+        let remapped = db_bit_page_vec.remap(&deleted);
+        assert_eq!(remapped.iter().collect_vec(), vec![0, 62, 67, 126, 696]);
+
+        let remapped = db_bit_page_vec.remap(&DbBitPageVec::all_zeros());
+        assert_eq!(remapped.iter().collect_vec(), db_bit_page_vec.iter().collect_vec());
+
+        assert!(matches!(deleted.remap(&deleted), DbBitPageVec::AllZeroes));
+    }
+
+    #[test]
+    fn test_remap_with() {
+        let db_bit_page_vec = vec![0, 2, 3, 8, 20].into_iter().collect::<DbBitPageVec>();
+
+        // reverse the first 10 ids... and drop id 2 (id 20 is past the end of mapping)
+        let mut mapping = (0..10u64).rev().collect_vec();
+        mapping[2] = REMAP_DROPPED;
+
+        let remapped = db_bit_page_vec.remap_with(&mapping);
+        assert_eq!(remapped.iter().collect_vec(), vec![1, 6, 9]);
+    }
+}
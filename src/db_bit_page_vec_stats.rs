@@ -0,0 +1,23 @@
+// @author shailendra.sharma
+use crate::bit_page_vec_stats::{BitPageVecStats, PageShape};
+use crate::{BitPage, DbBitPageVec};
+
+impl DbBitPageVec {
+    pub fn stats(&self) -> BitPageVecStats {
+        self.stats_with_density(0)
+    }
+
+    /// stats along with a histogram of active bits per bucket_bits wide bucket of ids (no histogram for 0)... pages
+    /// are counted upto the last stored page
+    pub fn stats_with_density(&self, bucket_bits: u64) -> BitPageVecStats {
+        let pages = self.pages();
+        let mut shape = PageShape::new(bucket_bits, 0);
+
+        for page in pages {
+            shape.add(page.page_idx, page.bit_page, BitPage::ones());
+        }
+
+        let span_pages = pages.last().map_or(0, |page| page.page_idx + 1);
+        shape.into_stats(self.kind(), None, pages.len(), self.estimated_bytes(), span_pages)
+    }
+}
@@ -3,9 +3,22 @@
 extern crate lazy_static;
 
 pub use bit_page::BitPage;
-pub use bit_page_vec::BitPageVec;
+pub use bit_page_vec::{BitPageVec, BitPageVecKind};
+pub use bit_page_vec_builder::BitPageVecBuilder;
+pub use bit_page_vec_cursor::PageCursor;
+pub use bit_page_vec_ids::BitPageVecIdsIterator;
+pub use bit_page_vec_iter::BitPageVecIter;
+pub use bit_page_vec_optimize::OptimizeReport;
+pub use bit_page_vec_rank::BitPageVecRankIndex;
+pub use bit_page_vec_remap::REMAP_DROPPED;
+pub use bit_page_vec_resize::GrowFill;
+pub use bit_page_vec_slice::BitPageVecSlice;
+pub use bit_page_vec_stats::{BitPageVecStats, BitPageVecStatsAggregate};
 pub use boolean_op::{BooleanOp, BooleanOpResult};
+pub use compaction_policy::{CompactionPolicy, CompactionStats, DefaultCompactionPolicy};
 pub use db_bit_page_vec::DbBitPageVec;
+pub use db_bit_page_vec_builder::DbBitPageVecBuilder;
+pub use universe::{Universe, UniversePolicy};
 
 // bit page and its associated modules
 mod bit_page;
@@ -15,10 +28,45 @@ mod bit_page_serde;
 // bit page vector and its associated modules
 mod bit_page_vec;
 mod bit_page_vec_active_bits;
+mod bit_page_vec_batch;
+mod bit_page_vec_builder;
+mod bit_page_vec_concat;
+mod bit_page_vec_containers;
+mod bit_page_vec_count;
+mod bit_page_vec_cursor;
+mod bit_page_vec_eq;
+#[cfg(test)]
+mod bit_page_vec_fixtures;
+mod bit_page_vec_ids;
 mod bit_page_vec_iter;
+mod bit_page_vec_navigate;
 mod bit_page_vec_ops;
+mod bit_page_vec_optimize;
+mod bit_page_vec_predicates;
+mod bit_page_vec_range;
+mod bit_page_vec_rank;
+mod bit_page_vec_remap;
+mod bit_page_vec_resize;
+mod bit_page_vec_rev;
+mod bit_page_vec_runs;
+mod bit_page_vec_shift;
+mod bit_page_vec_slice;
+mod bit_page_vec_stats;
 mod db_bit_page_vec;
+mod db_bit_page_vec_batch;
+mod db_bit_page_vec_builder;
+mod db_bit_page_vec_ids;
+mod db_bit_page_vec_navigate;
+mod db_bit_page_vec_optimize;
+mod db_bit_page_vec_predicates;
+mod db_bit_page_vec_range;
+mod db_bit_page_vec_remap;
 mod db_bit_page_vec_serde;
+mod db_bit_page_vec_stats;
 
 // boolean op
 mod boolean_op;
+mod compaction_policy;
+
+// universe (id space) of bit page vectors
+mod universe;
@@ -0,0 +1,235 @@
+use std::cmp::{max, min};
+use std::fmt;
+
+// @author shailendra.sharma
+use crate::bit_page::{join_bit_index, split_bit_index, zero_masks};
+use crate::BitPage;
+
+/// id space of a vector... bits [0, len) are addressable, i.e. len is exclusive
+#[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Universe {
+    len: u64,
+}
+
+impl Universe {
+    pub fn new(len: u64) -> Universe {
+        Universe { len }
+    }
+
+    #[inline]
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// exclusive (page_idx, bit_idx) position of the end of the universe
+    #[inline]
+    pub fn last_bit_index(&self) -> (usize, usize) {
+        split_bit_index(self.len)
+    }
+
+    #[inline]
+    pub fn contains(&self, index: u64) -> bool {
+        index < self.len
+    }
+
+    /// number of pages holding at least one addressable bit
+    #[inline]
+    pub fn pages(&self) -> usize {
+        let (last_page, last_bit) = self.last_bit_index();
+        if last_bit > 0 {
+            last_page + 1
+        } else {
+            last_page
+        }
+    }
+
+    /// addressable bits of page_idx... all ones before the tail page and zeroes after it
+    #[inline]
+    pub fn page_mask(&self, page_idx: usize) -> u64 {
+        let (last_page, last_bit) = self.last_bit_index();
+        if page_idx < last_page {
+            BitPage::ones()
+        } else if page_idx == last_page {
+            zero_masks()[last_bit]
+        } else {
+            BitPage::zeroes()
+        }
+    }
+
+    /// universe both vectors get combined into
+    pub fn combine(self, other: Universe, policy: UniversePolicy) -> Universe {
+        match policy {
+            UniversePolicy::Extend => max(self, other),
+            UniversePolicy::Truncate => min(self, other),
+        }
+    }
+}
+
+impl From<(usize, usize)> for Universe {
+    fn from(last_bit_index: (usize, usize)) -> Self {
+        Universe::new(join_bit_index(last_bit_index.0, last_bit_index.1))
+    }
+}
+
+impl From<u64> for Universe {
+    fn from(len: u64) -> Self {
+        Universe::new(len)
+    }
+}
+
+impl fmt::Debug for Universe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Universe(len={})", self.len)
+    }
+}
+
+/// how vectors with different universes are combined by binary operations
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum UniversePolicy {
+    // smaller universe is extended with zeroes up to the larger one
+    Extend,
+    // larger universe is truncated down to the smaller one... what binary operations always did
+    #[default]
+    Truncate,
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::bit_page_vec_fixtures::vectors;
+    use crate::bit_page_vec_iter::BitPageVecIter;
+    use crate::{BitPage, BitPageVec, BitPageVecKind, BooleanOp, Universe, UniversePolicy};
+
+    #[test]
+    fn test_universe() {
+        let universe = Universe::from((2, 5));
+        assert_eq!(universe.len(), 133);
+        assert_eq!(universe.pages(), 3);
+        assert_eq!(universe.page_mask(1), BitPage::ones());
+        assert_eq!(universe.page_mask(2), 0b1_1111);
+        assert_eq!(universe.page_mask(3), BitPage::zeroes());
+
+        let universe = Universe::from(128);
+        assert_eq!(universe.last_bit_index(), (2, 0));
+        assert_eq!(universe.pages(), 2);
+        assert_eq!(universe.page_mask(2), BitPage::zeroes());
+    }
+
+    #[test]
+    fn test_tail_is_masked() {
+        // bit set past the end is dropped... so it never shows up
+        let mut bit_page_vec = BitPageVec::all_zeros((1, 10));
+        bit_page_vec.set_bit(1, 20);
+        bit_page_vec.insert(3);
+        assert_eq!(bit_page_vec.active_bits_count(), 1);
+        assert!(!bit_page_vec.is_bit_set(1, 20));
+        assert_eq!(bit_page_vec.size(), 1);
+
+        // same for a ones hole... whose reads past the end are not the hole
+        let mut all_ones = BitPageVec::all_ones((1, 10));
+        assert!(!all_ones.is_bit_set(1, 20));
+        all_ones.clear_bit(1, 20);
+        assert_eq!(all_ones.kind(), &BitPageVecKind::AllOnes);
+
+        bit_page_vec.not();
+        assert_eq!(bit_page_vec.active_bits_count(), 73);
+        assert_eq!(bit_page_vec.iter().collect_vec(), (0..74).filter(|id| *id != 3).collect_vec());
+
+        let mut ones_hole = BitPageVec::all_ones((1, 10));
+        assert_eq!(ones_hole.active_bits_count(), 74);
+        ones_hole.remove(70);
+        assert_eq!(ones_hole.active_bits_count(), 73);
+        assert_eq!(ones_hole.active_bits().count(), 73);
+
+        ones_hole.not();
+        assert_eq!(ones_hole.iter().collect_vec(), vec![70]);
+    }
+
+    #[test]
+    fn test_universe_policy() {
+        let zeroes_hole = BitPageVec::from_ranges(vec![1..2, 100..101, 150..151], (3, 0));
+        let all_ones = BitPageVec::all_ones((1, 0));
+
+        // default policy truncates the larger universe down to the smaller one
+        let or = BitPageVecIter::or(zeroes_hole.page_iter(), all_ones.page_iter()).into_bit_page_vec();
+        assert_eq!(or.universe(), Universe::from(64));
+        assert_eq!(or.iter().collect_vec(), (0..64).collect_vec());
+
+        let (first, second) = BitPageVecIter::align(zeroes_hole.page_iter(), all_ones.page_iter(), UniversePolicy::Extend);
+        let or = BitPageVecIter::or(first, second).into_bit_page_vec();
+        assert_eq!(or.universe(), Universe::from((3, 0)));
+        assert_eq!(or.iter().collect_vec(), (0..64).chain(vec![100, 150]).collect_vec());
+
+        let mut ones_hole = BitPageVec::all_ones((1, 32));
+        ones_hole.remove(1);
+        let and = BitPageVecIter::and(zeroes_hole.page_iter(), ones_hole.page_iter()).into_bit_page_vec();
+        assert_eq!(and.universe(), Universe::from((1, 32)));
+        assert!(and.iter().next().is_none());
+
+        ones_hole.not();
+        let and = BitPageVecIter::and(zeroes_hole.page_iter(), ones_hole.page_iter()).into_bit_page_vec();
+        assert_eq!(and.iter().collect_vec(), vec![1]);
+
+        // extended with zeroes... so nothing more intersects
+        let (first, second) = BitPageVecIter::align(zeroes_hole.page_iter(), ones_hole.page_iter(), UniversePolicy::Extend);
+        let and = BitPageVecIter::and(first, second).into_bit_page_vec();
+        assert_eq!(and.universe(), Universe::from((3, 0)));
+        assert_eq!(and.iter().collect_vec(), vec![1]);
+    }
+
+    #[test]
+    fn test_universe_policy_of_vectors() {
+        let small = BitPageVec::from_ranges(vec![1..3, 60..70], (1, 32));
+        let large = BitPageVec::from_ranges(vec![2..4, 90..100, 150..160], (3, 0));
+
+        for policy in [UniversePolicy::Truncate, UniversePolicy::Extend] {
+            let universe = small.universe().combine(large.universe(), policy);
+            let within = |ids: Vec<u64>| ids.into_iter().filter(|id| *id < universe.len()).collect_vec();
+
+            let mut or = small.clone().with_universe_policy(policy);
+            or.or(&large);
+            assert_eq!(or.universe(), universe);
+            assert_eq!(or.universe_policy(), policy);
+            assert_eq!(or.iter().collect_vec(), within((1..4).chain(60..70).chain(90..100).chain(150..160).collect_vec()));
+
+            let mut and_not = large.clone().with_universe_policy(policy);
+            and_not.and_not(&small);
+            assert_eq!(and_not.universe(), universe);
+            assert_eq!(and_not.iter().collect_vec(), within(vec![3].into_iter().chain(90..100).chain(150..160).collect_vec()));
+
+            let first = small.clone().with_universe_policy(policy);
+            assert_eq!(first.or_count(&large), or.active_bits_count());
+            assert_eq!(first.and_count(&large), 1);
+            assert_eq!(first.xor_count(&large), or.active_bits_count() - 1);
+
+            // 150 is only there when the universe is extended
+            let subset = BitPageVec::from_ranges(vec![1..2, 150..151], (3, 0)).with_universe_policy(policy);
+            assert_eq!(subset.is_subset(&small), policy == UniversePolicy::Truncate);
+
+            // containers are counted by their offsets... within the universe they are combined into
+            let (first, second) = (vectors((40, 9)).remove(4), vectors((90, 0)).remove(4));
+            assert_eq!((first.kind(), second.kind()), (&BitPageVecKind::Containers, &BitPageVecKind::Containers));
+            let first = first.with_universe_policy(policy);
+            let mut union = first.clone();
+            union.or(&second);
+            assert_eq!(first.or_count(&second), union.active_bits_count());
+            let combined = first.universe().combine(second.universe(), policy);
+            let expected = first.iter().chain(second.iter()).filter(|id| *id < combined.len());
+            assert_eq!(union.iter().collect_vec(), expected.sorted().dedup().collect_vec());
+
+            // boolean ops combine their operands the same way
+            let result = BooleanOp::new_or_op(vec![BooleanOp::new_leaf_op(&small), BooleanOp::new_leaf_op(&large)])
+                .unwrap()
+                .evaluate_with(policy)
+                .into_bit_page_vec();
+            assert_eq!(result.universe(), universe);
+            assert_eq!(result.iter().collect_vec(), or.iter().collect_vec());
+        }
+    }
+}